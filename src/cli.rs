@@ -1,14 +1,216 @@
-use clap::Parser;
+use crate::options::{ImageFormat, PageNames, RecycleBin, Theme};
+use clap::{Args, Parser, Subcommand};
+use glob::Pattern;
+use regex::Regex;
 use std::path::PathBuf;
 
+/// The JPEG quality used if `--image-quality` isn't given.
+pub(crate) const DEFAULT_IMAGE_QUALITY: u8 = 85;
+
 #[derive(Parser, Debug)]
-#[command(name = "one2html")]
+#[command(
+    name = "one2html",
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub(crate) struct Opt {
-    /// Input files (`.one` or `.onetoc2` files)
-    #[arg(short, long, required = true, value_name = "FILE", num_args = 1..)]
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+
+    #[command(flatten)]
+    pub(crate) convert: ConvertOpt,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Convert notebooks and sections to HTML (the default when no command is given)
+    Convert(ConvertOpt),
+
+    /// Print the section groups, sections and pages of notebooks and sections
+    List(InputOpt),
+
+    /// Print the content structure, styles and note tags of pages
+    Inspect(InspectOpt),
+
+    /// Print statistics about the contents of notebooks and sections
+    Stats(StatsOpt),
+
+    /// Convert the input files and serve the output over HTTP
+    Serve(ServeOpt),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ConvertOpt {
+    /// Input files (`.one` or `.onetoc2` files) or folders to search for notebooks and sections
+    #[arg(short, long, required = true, value_name = "PATH", num_args = 1..)]
+    pub(crate) input: Vec<PathBuf>,
+
+    /// Output directory, or a `.zip`, `.tar.gz` or `.tgz` archive to write the output to
+    #[arg(short, long, value_name = "PATH", required = true)]
+    pub(crate) output: Option<PathBuf>,
+
+    /// Keep running and convert notebooks and sections again when their files change
+    #[arg(long)]
+    pub(crate) watch: bool,
+
+    #[command(flatten)]
+    pub(crate) render: RenderOpt,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct InputOpt {
+    /// Input files (`.one` or `.onetoc2` files) or folders to search for notebooks and sections
+    #[arg(short, long, required = true, value_name = "PATH", num_args = 1..)]
+    pub(crate) input: Vec<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct InspectOpt {
+    #[command(flatten)]
+    pub(crate) input: InputOpt,
+
+    /// Only inspect sections with the given name
+    #[arg(short, long, value_name = "NAME")]
+    pub(crate) section: Option<String>,
+
+    /// Only inspect pages whose title contains the given text
+    #[arg(short, long, value_name = "TITLE")]
+    pub(crate) page: Option<String>,
+
+    /// Print the data structures of the parser instead of a summary
+    #[arg(long)]
+    pub(crate) raw: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct StatsOpt {
+    #[command(flatten)]
+    pub(crate) input: InputOpt,
+
+    /// Print the statistics as JSON
+    #[arg(long)]
+    pub(crate) json: bool,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ServeOpt {
+    /// Input files (`.one` or `.onetoc2` files) or folders to search for notebooks and sections
+    #[arg(short, long, required = true, value_name = "PATH", num_args = 1..)]
     pub(crate) input: Vec<PathBuf>,
 
-    /// Output directory
+    /// Output directory (defaults to a temporary directory)
     #[arg(short, long, value_name = "DIR")]
-    pub(crate) output: PathBuf,
+    pub(crate) output: Option<PathBuf>,
+
+    /// Address to listen on
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1")]
+    pub(crate) bind: String,
+
+    /// Port to listen on
+    #[arg(short, long, value_name = "PORT", default_value_t = 8000)]
+    pub(crate) port: u16,
+
+    /// Convert changed files again and reload open pages in the browser
+    #[arg(long)]
+    pub(crate) watch: bool,
+
+    #[command(flatten)]
+    pub(crate) render: RenderOpt,
+}
+
+/// Options that control how notebooks are rendered.
+#[derive(Args, Debug)]
+pub(crate) struct RenderOpt {
+    /// Add semantic markup and ARIA attributes for assistive technologies
+    #[arg(long)]
+    pub(crate) accessible: bool,
+
+    /// Language of the notebook contents (used for the `lang` attribute)
+    #[arg(long, value_name = "LANG", default_value = "en")]
+    pub(crate) lang: String,
+
+    /// Color theme of the generated documents
+    #[arg(long, value_enum, value_name = "THEME", default_value_t = Theme::Light)]
+    pub(crate) theme: Theme,
+
+    /// How to handle deleted sections and pages in a notebook's recycle bin
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = RecycleBin::Exclude)]
+    pub(crate) recycle_bin: RecycleBin,
+
+    /// Store images and files once per notebook in a shared `assets` directory
+    #[arg(long)]
+    pub(crate) dedup_assets: bool,
+
+    /// Render consecutive monospace paragraphs as syntax-highlighted code blocks
+    #[arg(long)]
+    pub(crate) code_blocks: bool,
+
+    /// Render the first row of each table with more than one row as a header row (tables nested in table cells are left as they are)
+    #[arg(long)]
+    pub(crate) table_headers: bool,
+
+    /// Make tables with a header row sortable by clicking on a column header
+    #[arg(long)]
+    pub(crate) sortable_tables: bool,
+
+    /// Export every table as a CSV file linked below the table
+    #[arg(long)]
+    pub(crate) export_tables: bool,
+
+    /// Export the tables of each page as an XLSX workbook with one sheet per table
+    #[arg(long)]
+    pub(crate) export_tables_xlsx: bool,
+
+    /// Downscale images to the size they are displayed at
+    #[arg(long)]
+    pub(crate) resize_images: bool,
+
+    /// Display image thumbnails that link to the full-resolution original
+    #[arg(long)]
+    pub(crate) thumbnails: bool,
+
+    /// Re-encode images to the given format
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = ImageFormat::Original)]
+    pub(crate) image_format: ImageFormat,
+
+    /// Quality (1-100) used when re-encoding images as JPEG
+    #[arg(long, value_name = "QUALITY", default_value_t = DEFAULT_IMAGE_QUALITY, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub(crate) image_quality: u8,
+
+    /// Only convert sections whose path (`Notebook/Section Group/Section`) matches the glob pattern
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) include_sections: Vec<Pattern>,
+
+    /// Don't convert sections whose path matches the glob pattern
+    #[arg(long, value_name = "GLOB")]
+    pub(crate) exclude_sections: Vec<Pattern>,
+
+    /// Only convert pages whose title matches the regular expression
+    #[arg(long, value_name = "REGEX")]
+    pub(crate) include_pages: Vec<Regex>,
+
+    /// Don't convert pages whose title matches the regular expression
+    #[arg(long, value_name = "REGEX")]
+    pub(crate) exclude_pages: Vec<Regex>,
+
+    /// How to name the files of converted pages
+    #[arg(long, value_enum, value_name = "SCHEME", default_value_t = PageNames::Title)]
+    pub(crate) page_names: PageNames,
+
+    /// Write redirect pages for pages whose file name changed because of a different `--page-names` scheme or name suffix since the previous conversion (renamed pages aren't redirected)
+    #[arg(long)]
+    pub(crate) redirects: bool,
+
+    /// Use metric-compatible open fonts (e.g. Carlito for Calibri) as fallbacks
+    #[arg(long)]
+    pub(crate) substitute_fonts: bool,
+
+    /// File with additional font substitutes, one `Font = Substitute` mapping per line
+    #[arg(long, value_name = "FILE")]
+    pub(crate) font_map: Option<PathBuf>,
+
+    /// Directory with substitute font files to bundle into the export
+    #[arg(long, value_name = "DIR")]
+    pub(crate) font_dir: Option<PathBuf>,
 }
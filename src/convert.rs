@@ -0,0 +1,521 @@
+use crate::options::{Options, RecycleBin};
+use crate::output::Sink;
+use crate::utils::with_progress;
+use crate::{discovery, notebook, section, templates};
+use color_eyre::eyre::Result;
+use color_eyre::eyre::{ContextCompat, WrapErr, eyre};
+use console::style;
+use itertools::Itertools;
+use log::warn;
+use onenote_parser::Parser as OneNoteParser;
+use onenote_parser::notebook::Notebook;
+use onenote_parser::section::Section;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, io, process};
+
+/// A notebook or section to convert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Job {
+    /// The `.one` or `.onetoc2` file.
+    pub(crate) path: PathBuf,
+
+    /// The directory the output is written to.
+    pub(crate) output_dir: PathBuf,
+
+    /// The file was discovered in an input folder and is listed in the index.
+    pub(crate) indexed: bool,
+}
+
+/// Returns the conversion jobs for the input files and folders.
+pub(crate) fn jobs(inputs: &[PathBuf], output_dir: &Path) -> Result<Vec<Job>> {
+    let mut jobs = Vec::new();
+
+    for path in inputs {
+        if !path.is_dir() {
+            jobs.push(Job {
+                path: path.clone(),
+                output_dir: output_dir.to_path_buf(),
+                indexed: false,
+            });
+
+            continue;
+        }
+
+        let files = discovery::discover(path)?;
+
+        // Sections of notebooks are converted as part of the notebook
+        let notebook_dirs = files
+            .iter()
+            .filter(|file| file.extension().is_some_and(|ext| ext == "onetoc2"))
+            .filter_map(|file| file.parent())
+            .collect::<Vec<_>>();
+
+        for file in &files {
+            let is_section = file.extension().is_some_and(|ext| ext == "one");
+
+            if is_section && notebook_dirs.iter().any(|dir| file.starts_with(dir)) {
+                continue;
+            }
+
+            // Notebooks are folders, so their location is the folder's parent
+            let location = match file.extension().and_then(|ext| ext.to_str()) {
+                Some("onetoc2") => file.parent().and_then(Path::parent),
+                _ => file.parent(),
+            }
+            .unwrap_or(path);
+
+            // The input folder itself may be a notebook, whose location is
+            // outside of the input folder
+            let relative_dir = location.strip_prefix(path).unwrap_or(Path::new(""));
+
+            jobs.push(Job {
+                output_dir: output_dir.join(relative_dir),
+                path: file.clone(),
+                indexed: true,
+            });
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// Convert all input files and folders and return the paths of their tables
+/// of contents.
+///
+/// If folders are given, an index of all notebooks and sections found in
+/// them is written to the output directory.
+pub(crate) fn convert_all(
+    inputs: &[PathBuf],
+    output_dir: &Path,
+    options: &Options,
+    sink: &Sink,
+) -> Result<Vec<PathBuf>> {
+    let mut index = Vec::new();
+    let mut toc_files = Vec::new();
+
+    for job in jobs(inputs, output_dir)? {
+        sink.create_dir(&job.output_dir)?;
+
+        let Some(toc_file) = convert(&job.path, &job.output_dir, options, sink)? else {
+            continue;
+        };
+        toc_files.push(toc_file.clone());
+
+        if job.indexed {
+            index.push(templates::index::Entry {
+                name: toc_file
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                path: toc_file
+                    .strip_prefix(output_dir)?
+                    .to_string_lossy()
+                    .to_string(),
+            });
+        }
+    }
+
+    if !index.is_empty() {
+        let name = inputs
+            .iter()
+            .filter(|path| path.is_dir())
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy())
+            .join(", ");
+
+        let index_html = templates::index::render(&name, &index, options)?;
+        sink.write(&output_dir.join("index.html"), index_html)?;
+    }
+
+    Ok(toc_files)
+}
+
+/// Convert a section or notebook and return the path of its table of contents.
+///
+/// Returns `None` if the section has been excluded by the filters.
+pub(crate) fn convert(
+    path: &Path,
+    output_dir: &Path,
+    options: &Options,
+    sink: &Sink,
+) -> Result<Option<PathBuf>> {
+    let parser = OneNoteParser::new();
+
+    match path.extension().map(|p| p.to_string_lossy()).as_deref() {
+        Some("one") => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            println!("Processing section {}...", style(&name).bright());
+
+            let section = with_progress("Parsing input file...", || parser.parse_section(path))?;
+
+            if !options.filter.includes_section(section.display_name()) {
+                return Ok(None);
+            }
+
+            let mut renderer = section::Renderer::new(options.clone(), sink.clone());
+            let section_dir = renderer.render(&section, output_dir)?;
+
+            report_unmapped_fonts(&renderer.fonts, options);
+
+            match section_dir {
+                Some(_) => section::toc_file(&section, output_dir).map(Some),
+                None => Ok(None),
+            }
+        }
+        Some("onetoc2") => {
+            let name = path
+                .parent()
+                .unwrap()
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            println!("Processing notebook {}...", style(&name).bright());
+
+            let notebook_dir = path.parent().unwrap_or(path);
+            let notebook = with_progress("[1/2] Parsing input files...", || {
+                parse_notebook(&parser, path)
+            })?;
+            let entries = notebook.entries();
+
+            let notebook_name = notebook_name(path)?;
+
+            let deleted = if options.recycle_bin == RecycleBin::Exclude {
+                Vec::new()
+            } else {
+                parse_recycle_bin(&parser, notebook_dir)?
+            };
+
+            let mut renderer = notebook::Renderer::new(options.clone(), sink.clone());
+
+            let toc_file = with_progress("[2/2] Rendering sections...", || {
+                renderer.render(&entries, &deleted, &notebook_name, output_dir)
+            })?;
+
+            report_unmapped_fonts(&renderer.fonts, options);
+
+            Ok(Some(toc_file))
+        }
+        Some(ext) => Err(eyre!("Invalid file extension: {}", ext)),
+        _ => Err(eyre!("Couldn't determine file type")),
+    }
+}
+
+/// Convert a single section of a notebook without updating the notebook's
+/// table of contents.
+pub(crate) fn convert_notebook_section(
+    toc: &Path,
+    path: &Path,
+    output_dir: &Path,
+    options: &Options,
+    sink: &Sink,
+) -> Result<()> {
+    let parser = OneNoteParser::new();
+
+    let notebook_dir = toc.parent().wrap_err("Input file has no parent folder")?;
+    let notebook_name = notebook_dir
+        .file_name()
+        .wrap_err("Parent folder has no name")?
+        .to_string_lossy();
+    let group = path
+        .parent()
+        .filter(|dir| *dir != notebook_dir)
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy());
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    println!("Processing section {}...", style(&name).bright());
+
+    let section = with_progress("Parsing input file...", || parser.parse_section(path))?;
+
+    let mut renderer = notebook::Renderer::new(options.clone(), sink.clone());
+    renderer.render_single_section(&section, group.as_deref(), &notebook_name, output_dir)?;
+
+    report_unmapped_fonts(&renderer.fonts, options);
+
+    Ok(())
+}
+
+/// A parsed section or notebook.
+pub(crate) enum Input {
+    Section(Section),
+    Notebook(String, ParsedNotebook),
+}
+
+/// A parsed notebook.
+pub(crate) enum ParsedNotebook {
+    Notebook(Notebook),
+
+    /// The sections of a notebook that couldn't be parsed as a whole.
+    Sections(Vec<notebook::Entry<'static>>),
+}
+
+impl ParsedNotebook {
+    /// Returns the sections and section groups of the notebook.
+    pub(crate) fn entries(&self) -> Vec<notebook::Entry<'_>> {
+        match self {
+            ParsedNotebook::Notebook(notebook) => notebook::entries(notebook),
+            ParsedNotebook::Sections(entries) => {
+                entries.iter().map(notebook::Entry::borrowed).collect()
+            }
+        }
+    }
+}
+
+/// Parse a section or notebook without rendering it.
+pub(crate) fn parse(path: &Path) -> Result<Input> {
+    let parser = OneNoteParser::new();
+
+    match path.extension().map(|p| p.to_string_lossy()).as_deref() {
+        Some("one") => Ok(Input::Section(parser.parse_section(path)?)),
+        Some("onetoc2") => Ok(Input::Notebook(
+            notebook_name(path)?,
+            parse_notebook(&parser, path)?,
+        )),
+        Some(ext) => Err(eyre!("Invalid file extension: {}", ext)),
+        _ => Err(eyre!("Couldn't determine file type")),
+    }
+}
+
+/// Parse a notebook from its `.onetoc2` file.
+fn parse_notebook(parser: &OneNoteParser, path: &Path) -> Result<ParsedNotebook> {
+    let error = match parser.parse_notebook(path) {
+        Ok(notebook) => return Ok(ParsedNotebook::Notebook(notebook)),
+        Err(e) => e,
+    };
+
+    // A single section that can't be parsed (e.g. because it is password
+    // protected) fails the whole notebook. If that's the cause, parse a copy
+    // of the notebook without these sections and show them as locked.
+    let notebook_dir = path.parent().unwrap_or(path);
+    let unreadable = unreadable_sections(parser, notebook_dir)?;
+
+    if unreadable.is_empty() {
+        return Err(error).wrap_err("Failed to parse notebook");
+    }
+
+    warn!(
+        "Failed to parse notebook, skipping unreadable sections: {}",
+        error
+    );
+
+    let copy = NotebookCopy::new(notebook_dir, &unreadable)?;
+    let Ok(notebook) = parser.parse_notebook(&copy.dir.join(path.file_name().unwrap_or_default()))
+    else {
+        return Err(error).wrap_err("Failed to parse notebook");
+    };
+
+    let mut entries = notebook::entries(&notebook)
+        .into_iter()
+        .map(notebook::Entry::into_owned)
+        .collect();
+    add_locked_sections(&mut entries, notebook_dir, &unreadable)?;
+
+    Ok(ParsedNotebook::Sections(entries))
+}
+
+fn notebook_name(path: &Path) -> Result<String> {
+    Ok(path
+        .parent()
+        .wrap_err("Input file has no parent folder")?
+        .file_name()
+        .wrap_err("Parent folder has no name")?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// Returns the sections in a notebook folder and its section groups that
+/// can't be parsed.
+fn unreadable_sections(parser: &OneNoteParser, dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut sections = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            if path
+                .file_name()
+                .is_some_and(|name| name != discovery::RECYCLE_BIN)
+            {
+                sections.extend(unreadable_sections(parser, &path)?);
+            }
+
+            continue;
+        }
+
+        if !discovery::has_extension(&path, "one") {
+            continue;
+        }
+
+        if let Err(e) = parser.parse_section(&path) {
+            warn!(
+                "Skipping section {} that can't be parsed: {}",
+                path.file_stem().unwrap_or_default().to_string_lossy(),
+                e
+            );
+
+            sections.push(path);
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Add the unreadable sections referenced by the tables of contents of the
+/// notebook and its section groups as locked sections.
+///
+/// Their position in the table of contents isn't known, so they are added
+/// after the other sections of their notebook or section group.
+fn add_locked_sections(
+    entries: &mut Vec<notebook::Entry<'static>>,
+    dir: &Path,
+    unreadable: &[PathBuf],
+) -> Result<()> {
+    for entry in entries.iter_mut() {
+        if let notebook::Entry::SectionGroup(name, entries) = entry {
+            add_locked_sections(entries, &dir.join(name.as_str()), unreadable)?;
+        }
+    }
+
+    let mut toc = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if discovery::has_extension(&path, "onetoc2") {
+            toc.extend(fs::read(&path)?);
+        }
+    }
+
+    for path in unreadable.iter().filter(|path| path.parent() == Some(dir)) {
+        // The table of contents stores the file names of its sections as
+        // UTF-16 strings
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+
+        if !toc
+            .windows(file_name.len())
+            .any(|window| window == file_name)
+        {
+            continue;
+        }
+
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        entries.push(notebook::Entry::Locked(name.to_string()));
+    }
+
+    Ok(())
+}
+
+/// A temporary copy of a notebook folder without its unreadable sections.
+///
+/// The copy is removed when dropped.
+struct NotebookCopy {
+    dir: PathBuf,
+}
+
+impl NotebookCopy {
+    fn new(notebook_dir: &Path, skip: &[PathBuf]) -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        // Use a folder of our own, as the temporary directory may also hold
+        // the output (e.g. of the `serve` command)
+        let dir = loop {
+            let dir = env::temp_dir().join(format!(
+                "one2html-notebook-{}-{}",
+                process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+
+            match fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e).wrap_err("Failed to create a temporary folder"),
+            }
+        };
+
+        let copy = NotebookCopy { dir };
+
+        copy_notebook_dir(notebook_dir, &copy.dir, skip)
+            .wrap_err("Failed to copy notebook to a temporary folder")?;
+
+        Ok(copy)
+    }
+}
+
+impl Drop for NotebookCopy {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn copy_notebook_dir(from: &Path, to: &Path, skip: &[PathBuf]) -> Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let target = to.join(path.file_name().unwrap_or_default());
+
+        if path.is_dir() {
+            if path
+                .file_name()
+                .is_some_and(|name| name != discovery::RECYCLE_BIN)
+            {
+                copy_notebook_dir(&path, &target, skip)?;
+            }
+
+            continue;
+        }
+
+        let is_notebook_file =
+            discovery::has_extension(&path, "one") || discovery::has_extension(&path, "onetoc2");
+
+        if !is_notebook_file || skip.contains(&path) {
+            continue;
+        }
+
+        // Link the files instead of copying them where possible
+        if fs::hard_link(&path, &target).is_err() {
+            fs::copy(&path, &target)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse the sections in the recycle bin of a notebook, skipping sections
+/// that can't be parsed.
+fn parse_recycle_bin(parser: &OneNoteParser, notebook_dir: &Path) -> Result<Vec<Section>> {
+    let mut sections = Vec::new();
+
+    for path in discovery::recycle_bin_sections(notebook_dir)? {
+        match parser.parse_section(&path) {
+            Ok(section) => sections.push(section),
+            Err(e) => warn!(
+                "Skipping deleted section {}: {}",
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                e
+            ),
+        }
+    }
+
+    Ok(sections)
+}
+
+fn report_unmapped_fonts(fonts: &BTreeSet<String>, options: &Options) {
+    if !options.fonts.substitute {
+        return;
+    }
+
+    let unmapped = options.fonts.map.unmapped(fonts);
+
+    if !unmapped.is_empty() {
+        warn!("No substitute defined for fonts: {}", unmapped.join(", "));
+    }
+}
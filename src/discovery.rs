@@ -0,0 +1,80 @@
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The folder OneNote moves deleted sections and pages to.
+pub(crate) const RECYCLE_BIN: &str = "OneNote_RecycleBin";
+
+/// Recursively find the notebooks and sections in a directory.
+///
+/// Folders containing a `.onetoc2` file are notebooks, so the sections and
+/// section groups within them are rendered as part of the notebook and aren't
+/// returned separately. Any other `.one` files are returned as standalone
+/// sections. Recycle bin folders are skipped.
+pub(crate) fn discover(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    discover_dir(dir, &mut files)?;
+
+    Ok(files)
+}
+
+fn discover_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.file_name().is_some_and(|name| name == RECYCLE_BIN) {
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(dir)
+        .wrap_err_with(|| format!("Failed to read directory {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    let notebooks = entries
+        .iter()
+        .filter(|path| path.is_file() && has_extension(path, "onetoc2"))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if !notebooks.is_empty() {
+        files.extend(notebooks);
+
+        return Ok(());
+    }
+
+    for path in entries {
+        if path.is_dir() {
+            discover_dir(&path, files)?;
+        } else if has_extension(&path, "one") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the section files in the recycle bin of a notebook.
+pub(crate) fn recycle_bin_sections(notebook_dir: &Path) -> Result<Vec<PathBuf>> {
+    let dir = notebook_dir.join(RECYCLE_BIN);
+
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut sections = fs::read_dir(&dir)
+        .wrap_err("Failed to read recycle bin")?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref()
+                .is_ok_and(|path| path.is_file() && has_extension(path, "one"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    sections.sort();
+
+    Ok(sections)
+}
+
+pub(crate) fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+}
@@ -0,0 +1,158 @@
+use color_eyre::Result;
+use color_eyre::eyre::{WrapErr, eyre};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Office fonts and their metric-compatible open source substitutes.
+const DEFAULT_SUBSTITUTES: &[(&str, &str)] = &[
+    ("Calibri", "Carlito"),
+    ("Calibri Light", "Carlito"),
+    ("Cambria", "Caladea"),
+    ("Arial", "Liberation Sans"),
+    ("Helvetica", "Liberation Sans"),
+    ("Arial Narrow", "Liberation Sans Narrow"),
+    ("Times New Roman", "Liberation Serif"),
+    ("Courier New", "Liberation Mono"),
+    ("Georgia", "Gelasio"),
+];
+
+/// Maps font names used in notebooks to substitute fonts.
+#[derive(Debug, Clone)]
+pub(crate) struct FontMap {
+    substitutes: HashMap<String, String>,
+}
+
+impl Default for FontMap {
+    fn default() -> Self {
+        FontMap {
+            substitutes: DEFAULT_SUBSTITUTES
+                .iter()
+                .map(|(font, substitute)| (font.to_lowercase(), substitute.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl FontMap {
+    /// Add the mappings from a file to the font map.
+    ///
+    /// Each line of the file maps a font to its substitute using the format
+    /// `Font = Substitute`. Empty lines and lines starting with `#` are ignored.
+    pub(crate) fn load(&mut self, path: &Path) -> Result<()> {
+        let contents = fs::read_to_string(path).wrap_err("Failed to read font map")?;
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (font, substitute) = line
+                .split_once('=')
+                .ok_or_else(|| eyre!("Invalid font mapping in line {}: {}", i + 1, line))?;
+
+            self.substitutes
+                .insert(font.trim().to_lowercase(), substitute.trim().to_string());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn substitute(&self, font: &str) -> Option<&str> {
+        self.substitutes
+            .get(&font.trim().to_lowercase())
+            .map(|s| s.as_str())
+    }
+
+    /// Returns the fonts that have no substitute and aren't substitutes themselves.
+    pub(crate) fn unmapped<'a>(&self, fonts: &'a BTreeSet<String>) -> Vec<&'a str> {
+        fonts
+            .iter()
+            .map(|font| font.as_str())
+            .filter(|font| self.substitute(font).is_none() && !self.is_substitute(font))
+            .collect()
+    }
+
+    /// Returns whether the font is used as a substitute for another font.
+    fn is_substitute(&self, font: &str) -> bool {
+        self.substitutes
+            .values()
+            .any(|substitute| substitute.eq_ignore_ascii_case(font.trim()))
+    }
+}
+
+/// A font file providing one style of a font family.
+#[derive(Debug)]
+pub(crate) struct FontFile {
+    pub(crate) path: PathBuf,
+    pub(crate) weight: u16,
+    pub(crate) italic: bool,
+}
+
+impl FontFile {
+    pub(crate) fn format(&self) -> &'static str {
+        match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("otf") => "opentype",
+            Some("woff") => "woff",
+            Some("woff2") => "woff2",
+            _ => "truetype",
+        }
+    }
+}
+
+/// Find the files of a font family in a directory.
+///
+/// Files are matched by name, e.g. `Carlito-BoldItalic.ttf` provides the bold
+/// italic style of the `Carlito` family.
+pub(crate) fn find_font_files(dir: &Path, family: &str) -> Result<Vec<FontFile>> {
+    let family = normalize(family);
+    let mut files = Vec::new();
+
+    let entries = fs::read_dir(dir).wrap_err("Failed to read font directory")?;
+
+    for entry in entries {
+        let path = entry?.path();
+
+        let is_font = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext, "ttf" | "otf" | "woff" | "woff2"));
+
+        if !is_font {
+            continue;
+        }
+
+        let stem = normalize(&path.file_stem().unwrap_or_default().to_string_lossy());
+        let Some(style) = stem.strip_prefix(&family) else {
+            continue;
+        };
+
+        let is_family_style = ["regular", "bold", "italic", "oblique"]
+            .iter()
+            .fold(style.to_string(), |style, word| style.replace(word, ""))
+            .is_empty();
+
+        if !is_family_style {
+            continue;
+        }
+
+        files.push(FontFile {
+            weight: if style.contains("bold") { 700 } else { 400 },
+            italic: style.contains("italic") || style.contains("oblique"),
+            path,
+        });
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(files)
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
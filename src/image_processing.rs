@@ -0,0 +1,117 @@
+use crate::options::{ImageFormat, ImageOptions};
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader};
+use std::io::Cursor;
+
+/// Thumbnail bounds used for images that have no display size.
+const DEFAULT_THUMBNAIL_SIZE: u32 = 400;
+
+pub(crate) struct ProcessedImage {
+    /// The image that is linked to (or displayed, if there's no thumbnail).
+    pub(crate) data: Vec<u8>,
+
+    /// The thumbnail that is displayed in place of the full image.
+    pub(crate) thumbnail: Option<Vec<u8>>,
+
+    /// The file extension of the encoded images (without leading dot).
+    pub(crate) extension: &'static str,
+}
+
+/// Returns whether the image is a Windows metafile which browsers can't display.
+pub(crate) fn is_metafile(extension: &str) -> bool {
+    matches!(
+        extension
+            .trim_start_matches('.')
+            .to_ascii_lowercase()
+            .as_str(),
+        "emf" | "wmf" | "emz" | "wmz"
+    )
+}
+
+/// Resize and re-encode an image according to the image options.
+///
+/// The `bounds` are the maximum display width and height in pixels.
+pub(crate) fn process_image(
+    data: &[u8],
+    bounds: Option<(u32, u32)>,
+    options: &ImageOptions,
+) -> Result<ProcessedImage> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .wrap_err("Failed to read image")?;
+    let source_format = reader.format();
+    let image = reader.decode().wrap_err("Failed to decode image")?;
+
+    let format = match (options.format, source_format) {
+        (ImageFormat::Webp, _) => image::ImageFormat::WebP,
+        (ImageFormat::Jpeg, _) => image::ImageFormat::Jpeg,
+        (ImageFormat::Original, Some(format)) => format,
+        (ImageFormat::Original, None) => image::ImageFormat::Png,
+    };
+
+    let extension = format.extensions_str().first().copied().unwrap_or("png");
+
+    let thumbnail = if options.thumbnails {
+        let (width, height) = bounds.unwrap_or((DEFAULT_THUMBNAIL_SIZE, DEFAULT_THUMBNAIL_SIZE));
+
+        Some(encode(&downscale(&image, width, height), format, options)?)
+    } else {
+        None
+    };
+
+    let data = match bounds {
+        Some((width, height)) if options.resize && !options.thumbnails => {
+            encode(&downscale(&image, width, height), format, options)?
+        }
+        _ if options.format == ImageFormat::Original && source_format.is_some() => data.to_vec(),
+        _ => encode(&image, format, options)?,
+    };
+
+    Ok(ProcessedImage {
+        data,
+        thumbnail,
+        extension,
+    })
+}
+
+fn downscale(image: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    if image.width() <= width && image.height() <= height {
+        return image.clone();
+    }
+
+    image.resize(width.max(1), height.max(1), FilterType::Lanczos3)
+}
+
+fn encode(
+    image: &DynamicImage,
+    format: image::ImageFormat,
+    options: &ImageOptions,
+) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+
+    match format {
+        image::ImageFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut data, options.quality);
+            DynamicImage::ImageRgb8(image.to_rgb8())
+                .write_with_encoder(encoder)
+                .wrap_err("Failed to encode JPEG image")?;
+        }
+        image::ImageFormat::WebP => {
+            let encoder = WebPEncoder::new_lossless(&mut data);
+            DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_with_encoder(encoder)
+                .wrap_err("Failed to encode WebP image")?;
+        }
+        format => {
+            image
+                .write_to(&mut Cursor::new(&mut data), format)
+                .wrap_err("Failed to encode image")?;
+        }
+    }
+
+    Ok(data)
+}
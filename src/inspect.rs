@@ -0,0 +1,364 @@
+use crate::cli::InspectOpt;
+use crate::convert::{self, Input};
+use crate::notebook;
+use crate::utils::with_progress;
+use color_eyre::eyre::Result;
+use console::style;
+use itertools::Itertools;
+use onenote_parser::contents::{Content, EmbeddedObject, NoteTag, OutlineElement, OutlineItem};
+use onenote_parser::page::{Page, PageContent};
+use onenote_parser::property::rich_text::ParagraphStyling;
+use onenote_parser::section::Section;
+use std::path::Path;
+
+/// Print the content structure, styles and note tags of the pages matching
+/// the filters.
+pub(crate) fn inspect(opt: &InspectOpt) -> Result<()> {
+    for job in convert::jobs(&opt.input.input, Path::new(""))? {
+        let input = with_progress("Parsing input file...", || convert::parse(&job.path))?;
+
+        match input {
+            Input::Section(section) => inspect_section(&section, opt),
+            Input::Notebook(_, notebook) => {
+                for section in notebook::sections(&notebook.entries()) {
+                    inspect_section(section, opt);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn inspect_section(section: &Section, opt: &InspectOpt) {
+    if let Some(name) = &opt.section
+        && !section.display_name().eq_ignore_ascii_case(name)
+    {
+        return;
+    }
+
+    let pages = section
+        .page_series()
+        .iter()
+        .flat_map(|series| series.pages())
+        .filter(|page| match &opt.page {
+            Some(filter) => page
+                .title_text()
+                .unwrap_or_default()
+                .to_lowercase()
+                .contains(&filter.to_lowercase()),
+            None => true,
+        });
+
+    for page in pages {
+        println!(
+            "{} {} {}",
+            style(section.display_name()).bright(),
+            style("/").dim(),
+            style(page.title_text().unwrap_or("Untitled Page").trim()).bright()
+        );
+
+        if opt.raw {
+            println!("{:#?}", page);
+        } else {
+            print_page(page);
+        }
+
+        println!();
+    }
+}
+
+fn print_page(page: &Page) {
+    print_line(1, "Level", page.level());
+
+    if let Some(author) = page.author() {
+        print_line(1, "Author", author);
+    }
+
+    if let Some(height) = page.height() {
+        print_line(1, "Height", height);
+    }
+
+    for content in page.contents() {
+        match content {
+            PageContent::Outline(outline) => {
+                println!(
+                    "{}Outline at ({}, {})",
+                    indent(1),
+                    optional(outline.offset_horizontal()),
+                    optional(outline.offset_vertical())
+                );
+
+                if let Some(width) = outline.layout_max_width() {
+                    print_line(2, "Max width", width);
+                }
+
+                print_items(outline.items(), 2);
+            }
+            PageContent::Image(image) => {
+                println!("{}Image", indent(1));
+                print_image(image, 2);
+            }
+            PageContent::EmbeddedFile(file) => {
+                println!("{}Embedded file", indent(1));
+                print_embedded_file(file, 2);
+            }
+            PageContent::Ink(ink) => {
+                println!("{}Ink ({} strokes)", indent(1), ink.ink_strokes().len())
+            }
+            PageContent::Unknown => println!("{}Unknown content", indent(1)),
+        }
+    }
+}
+
+fn print_items(items: &[OutlineItem], depth: usize) {
+    for item in items {
+        match item {
+            OutlineItem::Group(group) => {
+                println!("{}Group (level {})", indent(depth), group.child_level());
+                print_items(group.outlines(), depth + 1);
+            }
+            OutlineItem::Element(element) => print_element(element, depth),
+        }
+    }
+}
+
+fn print_element(element: &OutlineElement, depth: usize) {
+    println!("{}Element (level {})", indent(depth), element.child_level());
+
+    for list in element.list_contents() {
+        let mut details = vec![format!("format {:?}", list.list_format().iter().join(""))];
+
+        if let Some(font) = list.list_font().or(list.font()) {
+            details.push(format!("font {}", font));
+        }
+
+        if let Some(restart) = list.list_restart() {
+            details.push(format!("restart at {}", restart));
+        }
+
+        print_line(depth + 1, "List", details.join(", "));
+    }
+
+    for content in element.contents() {
+        print_content(content, depth + 1);
+    }
+
+    print_items(element.children(), depth + 1);
+}
+
+fn print_content(content: &Content, depth: usize) {
+    match content {
+        Content::RichText(text) => {
+            println!("{}Text {:?}", indent(depth), text.text());
+
+            let paragraph_style = describe_style(text.paragraph_style());
+            if !paragraph_style.is_empty() {
+                print_line(depth + 1, "Paragraph style", paragraph_style);
+            }
+
+            let chars = text.text().chars().collect::<Vec<_>>();
+            let mut start = 0;
+
+            for (i, run_style) in text.text_run_formatting().iter().enumerate() {
+                let end = text
+                    .text_run_indices()
+                    .get(i)
+                    .map(|end| *end as usize)
+                    .unwrap_or(chars.len())
+                    .min(chars.len());
+
+                let run = chars[start.min(end)..end].iter().collect::<String>();
+
+                let run_style = describe_style(run_style);
+
+                println!(
+                    "{}Run {:?}: {}",
+                    indent(depth + 1),
+                    run,
+                    if run_style.is_empty() {
+                        "no formatting"
+                    } else {
+                        &run_style
+                    }
+                );
+
+                start = end;
+            }
+
+            print_note_tags(text.note_tags(), depth + 1);
+
+            if !text.math_inline_objects().is_empty() {
+                print_line(depth + 1, "Math objects", text.math_inline_objects().len());
+            }
+
+            let ink = text
+                .embedded_objects()
+                .iter()
+                .filter(|object| matches!(object, EmbeddedObject::Ink(_)))
+                .count();
+
+            if ink > 0 {
+                print_line(depth + 1, "Embedded ink", ink);
+            }
+        }
+        Content::Table(table) => {
+            println!(
+                "{}Table ({} rows, {} columns{})",
+                indent(depth),
+                table.rows(),
+                table.cols(),
+                if table.borders_visible() {
+                    ""
+                } else {
+                    ", no borders"
+                }
+            );
+
+            print_note_tags(table.note_tags(), depth + 1);
+
+            for (r, row) in table.contents().iter().enumerate() {
+                for (c, cell) in row.contents().iter().enumerate() {
+                    println!("{}Cell {}:{}", indent(depth + 1), r + 1, c + 1);
+
+                    for element in cell.contents() {
+                        print_element(element, depth + 2);
+                    }
+                }
+            }
+        }
+        Content::Image(image) => {
+            println!("{}Image", indent(depth));
+            print_image(image, depth + 1);
+        }
+        Content::EmbeddedFile(file) => {
+            println!("{}Embedded file", indent(depth));
+            print_embedded_file(file, depth + 1);
+        }
+        Content::Ink(ink) => {
+            println!("{}Ink ({} strokes)", indent(depth), ink.ink_strokes().len())
+        }
+        Content::Unknown => println!("{}Unknown content", indent(depth)),
+    }
+}
+
+fn print_image(image: &onenote_parser::contents::Image, depth: usize) {
+    if let Some(name) = image.image_filename() {
+        print_line(depth, "File name", name);
+    }
+
+    print_line(depth, "Format", image.extension().unwrap_or("unknown"));
+    print_line(
+        depth,
+        "Size",
+        format!("{} bytes", image.data().map(|data| data.len()).unwrap_or(0)),
+    );
+
+    if let (Some(width), Some(height)) = (image.layout_max_width(), image.layout_max_height()) {
+        print_line(depth, "Display size", format!("{} x {}", width, height));
+    }
+
+    if let Some(alt) = image.alt_text() {
+        print_line(depth, "Alt text", format!("{:?}", alt));
+    }
+
+    if let Some(text) = image.text() {
+        print_line(depth, "Recognized text", format!("{:?}", text));
+    }
+
+    if image.is_background() {
+        print_line(depth, "Background", true);
+    }
+
+    print_note_tags(image.note_tags(), depth);
+}
+
+fn print_embedded_file(file: &onenote_parser::contents::EmbeddedFile, depth: usize) {
+    print_line(depth, "File name", file.filename());
+    print_line(depth, "Type", format!("{:?}", file.file_type()));
+    print_line(depth, "Size", format!("{} bytes", file.data().len()));
+    print_note_tags(file.note_tags(), depth);
+}
+
+fn print_note_tags(tags: &[NoteTag], depth: usize) {
+    for tag in tags {
+        let mut details = Vec::new();
+
+        if let Some(definition) = tag.definition() {
+            details.push(format!("{:?}", definition.label()));
+            details.push(format!("shape {:?}", definition.shape()));
+        }
+
+        if tag.item_status().completed() {
+            details.push("completed".to_string());
+        }
+
+        if tag.item_status().disabled() {
+            details.push("disabled".to_string());
+        }
+
+        print_line(depth, "Note tag", details.join(", "));
+    }
+}
+
+fn describe_style(style: &ParagraphStyling) -> String {
+    let mut details = Vec::new();
+
+    if let Some(id) = style.style_id() {
+        details.push(format!("style {}", id));
+    }
+
+    if let Some(font) = style.font() {
+        details.push(font.to_string());
+    }
+
+    if let Some(size) = style.font_size() {
+        details.push(format!("{}pt", size as f32 / 2.0));
+    }
+
+    let flags = [
+        (style.bold(), "bold"),
+        (style.italic(), "italic"),
+        (style.underline(), "underline"),
+        (style.strikethrough(), "strikethrough"),
+        (style.superscript(), "superscript"),
+        (style.subscript(), "subscript"),
+        (style.hyperlink(), "hyperlink"),
+        (style.math_formatting(), "math"),
+    ];
+
+    details.extend(
+        flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| name.to_string()),
+    );
+
+    if let Some(color) = style.font_color() {
+        details.push(format!("color {:?}", color));
+    }
+
+    if let Some(color) = style.highlight() {
+        details.push(format!("highlight {:?}", color));
+    }
+
+    if let Some(alignment) = style.paragraph_alignment() {
+        details.push(format!("alignment {:?}", alignment));
+    }
+
+    details.join(", ")
+}
+
+fn print_line(depth: usize, label: &str, value: impl std::fmt::Display) {
+    println!("{}{}: {}", indent(depth), style(label).dim(), value);
+}
+
+fn optional(value: Option<f32>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
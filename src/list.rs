@@ -0,0 +1,105 @@
+use crate::convert::{self, Input};
+use crate::notebook::{self, Entry};
+use crate::utils::with_progress;
+use color_eyre::eyre::Result;
+use console::style;
+use onenote_parser::section::Section;
+use std::path::{Path, PathBuf};
+
+/// Print the section groups, sections and pages of the input files.
+pub(crate) fn list(inputs: &[PathBuf]) -> Result<()> {
+    for job in convert::jobs(inputs, Path::new(""))? {
+        let input = with_progress("Parsing input file...", || convert::parse(&job.path))?;
+
+        match input {
+            Input::Section(section) => print_section(&section, 0),
+            Input::Notebook(name, notebook) => {
+                let entries = notebook.entries();
+
+                println!(
+                    "{} {}",
+                    style(name).bright(),
+                    style(format!("(notebook, {})", pages(&entries))).dim()
+                );
+
+                print_entries(&entries, 1);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_entries(entries: &[Entry], depth: usize) {
+    for entry in entries {
+        match entry {
+            Entry::Section(section) => print_section(section, depth),
+            Entry::SectionGroup(name, entries) => {
+                println!(
+                    "{}{} {}",
+                    indent(depth),
+                    style(name).bright(),
+                    style(format!("(section group, {})", pages(entries))).dim()
+                );
+
+                print_entries(entries, depth + 1);
+            }
+            Entry::Locked(name) => {
+                println!("{}{} {}", indent(depth), name, style("(locked)").dim());
+            }
+        }
+    }
+}
+
+fn print_section(section: &Section, depth: usize) {
+    println!(
+        "{}{} {}",
+        indent(depth),
+        style(section.display_name()).bright(),
+        style(format!("({})", page_count(page_total(section)))).dim()
+    );
+
+    let mut fallback_title_index = 0;
+
+    for page_series in section.page_series() {
+        for page in page_series.pages() {
+            let title = page.title_text().map(|s| s.to_string()).unwrap_or_else(|| {
+                fallback_title_index += 1;
+
+                format!("Untitled Page {}", fallback_title_index)
+            });
+
+            let level = page.level().max(1) as usize;
+
+            println!("{}{}", indent(depth + level), title.trim());
+        }
+    }
+}
+
+fn pages(entries: &[Entry]) -> String {
+    page_count(
+        notebook::sections(entries)
+            .into_iter()
+            .map(page_total)
+            .sum(),
+    )
+}
+
+fn page_total(section: &Section) -> usize {
+    section
+        .page_series()
+        .iter()
+        .map(|series| series.pages().len())
+        .sum()
+}
+
+fn page_count(count: usize) -> String {
+    match count {
+        1 => "1 page".to_string(),
+        count => format!("{} pages", count),
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
@@ -1,18 +1,32 @@
-use crate::cli::Opt;
-use crate::utils::with_progress;
+use crate::cli::{Command, ConvertOpt, DEFAULT_IMAGE_QUALITY, Opt, RenderOpt, ServeOpt};
+use crate::options::{ImageFormat, Options};
+use crate::output::Sink;
 use clap::Parser;
-use color_eyre::eyre::Result;
-use color_eyre::eyre::{ContextCompat, eyre};
+use color_eyre::eyre::{ContextCompat, Result, eyre};
 use console::style;
-use onenote_parser::Parser as OneNoteParser;
+use log::warn;
 use std::path::Path;
 use std::process::exit;
+use std::sync::atomic::Ordering;
+use std::{env, fs, process, thread};
 mod cli;
+mod convert;
+mod discovery;
+mod font_map;
+mod image_processing;
+mod inspect;
+mod list;
 mod notebook;
+mod options;
+mod output;
 mod page;
+mod redirects;
 mod section;
+mod serve;
+mod stats;
 mod templates;
 mod utils;
+mod watch;
 
 #[cfg(feature = "backtrace")]
 fn main() {
@@ -49,55 +63,102 @@ fn _main() -> Result<()> {
 
     color_eyre::install()?;
 
-    let output_dir = opt.output;
-    assert!(!output_dir.is_file());
+    match opt.command {
+        Some(Command::Convert(opt)) => run_convert(opt),
+        Some(Command::List(opt)) => list::list(&opt.input),
+        Some(Command::Inspect(opt)) => inspect::inspect(&opt),
+        Some(Command::Stats(opt)) => stats::stats(&opt),
+        Some(Command::Serve(opt)) => run_serve(opt),
+        None => run_convert(opt.convert),
+    }
+}
+
+fn run_convert(opt: ConvertOpt) -> Result<()> {
+    let options = options(&opt.render)?;
+
+    let output = opt.output.wrap_err("No output directory given")?;
+
+    if output::is_archive(&output) {
+        if opt.watch {
+            return Err(eyre!("Watch mode can't be used with archive output"));
+        }
+
+        let sink = Sink::new(&output)?;
 
-    for path in opt.input {
-        convert(&path, &output_dir)?;
+        // Files are stored relative to the root of the archive
+        convert::convert_all(&opt.input, Path::new(""), &options, &sink)?;
+
+        return sink.finish();
+    }
+
+    let output_dir = output;
+    if output_dir.is_file() {
+        return Err(eyre!(
+            "Output path {} is a file, not a directory",
+            output_dir.display()
+        ));
+    }
+
+    convert::convert_all(&opt.input, &output_dir, &options, &Sink::Directory)?;
+
+    if opt.watch {
+        watch::watch(&opt.input, &output_dir, &options, || {})?;
     }
 
     Ok(())
 }
 
-fn convert(path: &Path, output_dir: &Path) -> Result<()> {
-    let parser = OneNoteParser::new();
+fn run_serve(opt: ServeOpt) -> Result<()> {
+    let options = options(&opt.render)?;
 
-    match path.extension().map(|p| p.to_string_lossy()).as_deref() {
-        Some("one") => {
-            let name = path.file_name().unwrap_or_default().to_string_lossy();
-            println!("Processing section {}...", style(&name).bright());
+    let output_dir = opt
+        .output
+        .unwrap_or_else(|| env::temp_dir().join(format!("one2html-{}", process::id())));
+    fs::create_dir_all(&output_dir)?;
 
-            let section = with_progress("Parsing input file...", || parser.parse_section(path))?;
+    let toc_files = convert::convert_all(&opt.input, &output_dir, &options, &Sink::Directory)?;
 
-            section::Renderer::new().render(&section, output_dir)?;
-        }
-        Some("onetoc2") => {
-            let name = path
-                .parent()
-                .unwrap()
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy();
-            println!("Processing notebook {}...", style(&name).bright());
-
-            let notebook = with_progress("[1/2] Parsing input files...", || {
-                parser.parse_notebook(path)
-            })?;
-
-            let notebook_name = path
-                .parent()
-                .wrap_err("Input file has no parent folder")?
-                .file_name()
-                .wrap_err("Parent folder has no name")?
-                .to_string_lossy();
-
-            with_progress("[2/2] Rendering sections...", || {
-                notebook::Renderer::new().render(&notebook, &notebook_name, output_dir)
-            })?;
-        }
-        Some(ext) => return Err(eyre!("Invalid file extension: {}", ext)),
-        _ => return Err(eyre!("Couldn't determine file type")),
+    let start_page = if output_dir.join("index.html").is_file() {
+        output_dir.join("index.html")
+    } else {
+        toc_files
+            .first()
+            .cloned()
+            .wrap_err("No notebooks or sections found")?
+    };
+
+    let server = serve::Server::bind(&opt.bind, opt.port, &output_dir, &start_page, opt.watch)?;
+
+    println!(
+        "Serving {} at {}",
+        style(output_dir.display()).bright(),
+        style(server.url()).bright()
+    );
+
+    if !opt.watch {
+        server.run();
+
+        return Ok(());
     }
 
-    Ok(())
+    let version = server.version();
+    thread::spawn(move || server.run());
+
+    watch::watch(&opt.input, &output_dir, &options, || {
+        version.fetch_add(1, Ordering::SeqCst);
+    })
+}
+
+fn options(opt: &RenderOpt) -> Result<Options> {
+    let mut options = Options::from(opt);
+
+    if opt.image_format == ImageFormat::Webp && opt.image_quality != DEFAULT_IMAGE_QUALITY {
+        warn!("WebP images are encoded losslessly, ignoring --image-quality");
+    }
+
+    if let Some(path) = &opt.font_map {
+        options.fonts.map.load(path)?;
+    }
+
+    Ok(options)
 }
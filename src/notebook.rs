@@ -1,3 +1,6 @@
+use crate::discovery::RECYCLE_BIN;
+use crate::options::{Options, RecycleBin};
+use crate::output::Sink;
 use crate::templates::notebook::Toc;
 use crate::utils::sanitize_output_filename;
 use crate::{section, templates};
@@ -7,91 +10,325 @@ use onenote_parser::property::common::Color;
 use onenote_parser::section::{Section, SectionEntry};
 use palette::rgb::Rgb;
 use palette::{Alpha, Darken, FromColor, Hsl, Saturate, Srgb};
-use std::fs;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 
 pub(crate) type RgbColor = Alpha<Rgb<palette::encoding::Srgb, u8>, f32>;
 
-pub(crate) struct Renderer;
+/// A section or section group of a notebook.
+pub(crate) enum Entry<'a> {
+    Section(Cow<'a, Section>),
+    SectionGroup(String, Vec<Entry<'a>>),
 
-impl Renderer {
-    pub fn new() -> Self {
-        Renderer
+    /// A section that couldn't be parsed, e.g. because it is password protected.
+    Locked(String),
+}
+
+/// Returns the entries of a parsed notebook.
+pub(crate) fn entries(notebook: &Notebook) -> Vec<Entry<'_>> {
+    fn convert(entry: &SectionEntry) -> Entry<'_> {
+        match entry {
+            SectionEntry::Section(section) => Entry::Section(Cow::Borrowed(section)),
+            SectionEntry::SectionGroup(group) => Entry::SectionGroup(
+                group.display_name().to_string(),
+                group.entries().iter().map(convert).collect(),
+            ),
+        }
     }
 
-    pub fn render(&mut self, notebook: &Notebook, name: &str, output_dir: &Path) -> Result<()> {
-        if !output_dir.is_dir() {
-            fs::create_dir(output_dir)?;
+    notebook.entries().iter().map(convert).collect()
+}
+
+impl Entry<'_> {
+    /// Returns a copy of the entry that borrows its sections.
+    pub(crate) fn borrowed(&self) -> Entry<'_> {
+        match self {
+            Entry::Section(section) => Entry::Section(Cow::Borrowed(section)),
+            Entry::SectionGroup(name, entries) => {
+                Entry::SectionGroup(name.clone(), entries.iter().map(Entry::borrowed).collect())
+            }
+            Entry::Locked(name) => Entry::Locked(name.clone()),
         }
+    }
+}
 
-        let notebook_dir = output_dir.join(sanitize_filename::sanitize(name));
+impl Entry<'_> {
+    /// Returns a copy of the entry that owns its sections.
+    pub(crate) fn into_owned(self) -> Entry<'static> {
+        match self {
+            Entry::Section(section) => Entry::Section(Cow::Owned(section.into_owned())),
+            Entry::SectionGroup(name, entries) => {
+                Entry::SectionGroup(name, entries.into_iter().map(Entry::into_owned).collect())
+            }
+            Entry::Locked(name) => Entry::Locked(name),
+        }
+    }
+}
+
+/// Returns the sections of the entries, including the sections in section groups.
+pub(crate) fn sections<'b>(entries: &'b [Entry<'_>]) -> Vec<&'b Section> {
+    entries
+        .iter()
+        .flat_map(|entry| match entry {
+            Entry::Section(section) => vec![section.as_ref()],
+            Entry::SectionGroup(_, entries) => sections(entries),
+            Entry::Locked(_) => vec![],
+        })
+        .collect()
+}
+
+pub(crate) struct Renderer {
+    options: Options,
+    sink: Sink,
+    assets_dir: Option<PathBuf>,
+    fonts_dir: Option<PathBuf>,
+
+    /// The fonts used in the rendered sections.
+    pub(crate) fonts: BTreeSet<String>,
+}
 
-        if !notebook_dir.is_dir() {
-            fs::create_dir(&notebook_dir)?;
+impl Renderer {
+    pub fn new(options: Options, sink: Sink) -> Self {
+        Renderer {
+            options,
+            sink,
+            assets_dir: None,
+            fonts_dir: None,
+            fonts: BTreeSet::new(),
         }
+    }
+
+    /// Render a notebook along with the sections from its recycle bin.
+    pub fn render(
+        &mut self,
+        entries: &[Entry],
+        deleted: &[Section],
+        name: &str,
+        output_dir: &Path,
+    ) -> Result<PathBuf> {
+        let notebook_dir = self.prepare_notebook_dir(name, output_dir)?;
 
         let mut toc = Vec::new();
 
-        for entry in notebook.entries() {
+        for entry in entries {
             match entry {
-                SectionEntry::Section(section) => {
-                    toc.push(Toc::Section(self.render_section(
-                        section,
-                        &notebook_dir,
-                        output_dir,
-                    )?));
+                Entry::Section(section) => {
+                    let path = format!("{}/{}", name, section.display_name());
+
+                    if let Some(section) =
+                        self.render_section(section, &path, &notebook_dir, output_dir)?
+                    {
+                        toc.push(Toc::Section(section));
+                    }
                 }
-                SectionEntry::SectionGroup(group) => {
-                    let dir_name = sanitize_filename::sanitize(group.display_name());
+                Entry::SectionGroup(group_name, group_entries) => {
+                    let dir_name = sanitize_filename::sanitize(group_name);
                     let group_dir = notebook_dir.join(dir_name);
-                    if !group_dir.is_dir() {
-                        fs::create_dir(&group_dir)?;
-                    }
 
                     let mut entries = Vec::new();
 
-                    for entry in group.entries() {
-                        if let SectionEntry::Section(section) = entry {
-                            entries.push(self.render_section(section, &group_dir, output_dir)?);
-                        } else {
-                            return Err(eyre!("Nested section groups are not yet supported"));
+                    for entry in group_entries {
+                        match entry {
+                            Entry::Section(section) => {
+                                let path =
+                                    format!("{}/{}/{}", name, group_name, section.display_name());
+
+                                if let Some(section) =
+                                    self.render_section(section, &path, &group_dir, output_dir)?
+                                {
+                                    entries.push(section);
+                                }
+                            }
+                            Entry::Locked(section_name) => {
+                                let path = format!("{}/{}/{}", name, group_name, section_name);
+
+                                if self.options.filter.includes_section(&path) {
+                                    entries.push(locked_section(section_name));
+                                }
+                            }
+                            Entry::SectionGroup(..) => {
+                                return Err(eyre!("Nested section groups are not yet supported"));
+                            }
                         }
                     }
 
+                    // Leave out section groups whose sections have all been filtered
+                    if entries.is_empty() && !group_entries.is_empty() {
+                        continue;
+                    }
+
+                    self.sink.create_dir(&group_dir)?;
+
                     toc.push(templates::notebook::Toc::SectionGroup(
-                        group.display_name().to_string(),
+                        group_name.to_string(),
                         entries,
                     ))
                 }
+                Entry::Locked(section_name) => {
+                    let path = format!("{}/{}", name, section_name);
+
+                    if self.options.filter.includes_section(&path) {
+                        toc.push(Toc::Section(locked_section(section_name)));
+                    }
+                }
             }
         }
 
-        let toc_html = templates::notebook::render(name, &toc)?;
+        if !deleted.is_empty()
+            && let Some(deleted) = self.render_deleted(deleted, name, &notebook_dir, output_dir)?
+        {
+            toc.push(deleted);
+        }
+
+        let toc_html = templates::notebook::render(name, &toc, &self.options)?;
         let toc_name = sanitize_output_filename(name)? + ".html";
         let toc_file = output_dir.join(toc_name);
-        fs::write(toc_file, toc_html)?;
+        self.sink.write(&toc_file, toc_html)?;
+
+        Ok(toc_file)
+    }
+
+    /// Render a single section of a notebook that has been rendered before.
+    ///
+    /// The `group` is the name of the section group containing the section.
+    /// The notebook's table of contents isn't updated.
+    pub fn render_single_section(
+        &mut self,
+        section: &Section,
+        group: Option<&str>,
+        name: &str,
+        output_dir: &Path,
+    ) -> Result<()> {
+        let notebook_dir = self.prepare_notebook_dir(name, output_dir)?;
+
+        let (section_dir, path) = match group {
+            Some(group) => (
+                notebook_dir.join(sanitize_filename::sanitize(group)),
+                format!("{}/{}/{}", name, group, section.display_name()),
+            ),
+            None => (
+                notebook_dir.clone(),
+                format!("{}/{}", name, section.display_name()),
+            ),
+        };
+
+        self.render_section(section, &path, &section_dir, output_dir)?;
 
         Ok(())
     }
 
+    fn prepare_notebook_dir(&mut self, name: &str, output_dir: &Path) -> Result<PathBuf> {
+        let notebook_dir = output_dir.join(sanitize_filename::sanitize(name));
+
+        self.sink.create_dir(&notebook_dir)?;
+
+        if self.options.dedup_assets {
+            self.assets_dir = Some(notebook_dir.join("assets"));
+        }
+
+        if self.options.fonts.dir.is_some() {
+            self.fonts_dir = Some(notebook_dir.join("fonts"));
+        }
+
+        Ok(notebook_dir)
+    }
+
+    /// Render the sections from a notebook's recycle bin.
+    ///
+    /// Returns `None` if the filters exclude all deleted sections.
+    fn render_deleted(
+        &mut self,
+        deleted: &[Section],
+        name: &str,
+        notebook_dir: &Path,
+        base_dir: &Path,
+    ) -> Result<Option<Toc>> {
+        let deleted_dir = notebook_dir.join(RECYCLE_BIN);
+
+        let mut sections = Vec::new();
+
+        for section in deleted {
+            let path = format!("{}/{}/{}", name, RECYCLE_BIN, section.display_name());
+            let base_dir = match self.options.recycle_bin {
+                RecycleBin::Separate => notebook_dir,
+                _ => base_dir,
+            };
+
+            if let Some(section) = self.render_section(section, &path, &deleted_dir, base_dir)? {
+                sections.push(section);
+            }
+        }
+
+        if sections.is_empty() {
+            return Ok(None);
+        }
+
+        match self.options.recycle_bin {
+            RecycleBin::Separate => {
+                let toc = sections.into_iter().map(Toc::Section).collect::<Vec<_>>();
+
+                let toc_name = format!("{} - Deleted items", name);
+                let toc_html = templates::notebook::render(&toc_name, &toc, &self.options)?;
+                self.sink
+                    .write(&deleted_dir.with_extension("html"), toc_html)?;
+
+                Ok(Some(Toc::DeletedItems(
+                    deleted_dir
+                        .strip_prefix(base_dir)?
+                        .to_string_lossy()
+                        .to_string(),
+                )))
+            }
+            _ => Ok(Some(Toc::SectionGroup("Recycle Bin".to_string(), sections))),
+        }
+    }
+
+    /// Render a section if its path matches the section filters.
     fn render_section(
         &mut self,
         section: &Section,
+        path: &str,
         notebook_dir: &Path,
         base_dir: &Path,
-    ) -> Result<templates::notebook::Section> {
-        let mut renderer = section::Renderer::new();
-        let path = renderer.render(section, notebook_dir)?;
+    ) -> Result<Option<templates::notebook::Section>> {
+        if !self.options.filter.includes_section(path) {
+            return Ok(None);
+        }
+
+        let mut renderer = section::Renderer::new(self.options.clone(), self.sink.clone());
+
+        renderer.assets_dir = self.assets_dir.clone();
+        renderer.fonts_dir = self.fonts_dir.clone();
+
+        let Some(section_dir) = renderer.render(section, notebook_dir)? else {
+            return Ok(None);
+        };
+
+        self.fonts.append(&mut renderer.fonts);
 
-        Ok(templates::notebook::Section {
+        Ok(Some(templates::notebook::Section {
             name: section.display_name().to_string(),
-            path: path.strip_prefix(base_dir)?.to_string_lossy().to_string(),
+            path: section_dir
+                .strip_prefix(base_dir)?
+                .to_string_lossy()
+                .to_string(),
             color: section.color().map(prepare_color),
-        })
+            locked: false,
+        }))
+    }
+}
+
+fn locked_section(name: &str) -> templates::notebook::Section {
+    templates::notebook::Section {
+        name: name.to_string(),
+        path: String::new(),
+        color: None,
+        locked: true,
     }
 }
 
-fn prepare_color(color: Color) -> RgbColor {
+pub(crate) fn prepare_color(color: Color) -> RgbColor {
     Alpha {
         alpha: color.alpha() as f32 / 255.0,
         color: Srgb::from_color(
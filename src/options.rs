@@ -0,0 +1,228 @@
+use crate::cli::RenderOpt;
+use crate::font_map::FontMap;
+use clap::ValueEnum;
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use std::path::PathBuf;
+
+/// Rendering options shared by the notebook, section and page renderers.
+#[derive(Debug, Clone)]
+pub(crate) struct Options {
+    /// Add semantic markup and ARIA attributes to the rendered output.
+    pub(crate) accessible: bool,
+
+    /// The language code used for the `lang` attribute of generated documents.
+    pub(crate) lang: String,
+
+    /// Store images and files by content hash in a shared assets directory.
+    pub(crate) dedup_assets: bool,
+
+    /// Merge consecutive monospace paragraphs into highlighted code blocks.
+    pub(crate) code_blocks: bool,
+
+    /// Render the first row of each table as a header row.
+    pub(crate) table_headers: bool,
+
+    /// Allow sorting tables with a header row by clicking on a column header.
+    pub(crate) sortable_tables: bool,
+
+    /// Write the contents of every table to a CSV file.
+    pub(crate) export_tables_csv: bool,
+
+    /// Write the tables of every page to an XLSX workbook.
+    pub(crate) export_tables_xlsx: bool,
+
+    /// Image post-processing settings.
+    pub(crate) images: ImageOptions,
+
+    /// Font substitution settings.
+    pub(crate) fonts: FontOptions,
+
+    /// The color theme of the generated documents.
+    pub(crate) theme: Theme,
+
+    /// How the sections in a notebook's recycle bin are rendered.
+    pub(crate) recycle_bin: RecycleBin,
+
+    /// The sections and pages to render.
+    pub(crate) filter: Filter,
+
+    /// How page files are named.
+    pub(crate) page_names: PageNames,
+
+    /// Redirect the previous file names of pages whose file name changed
+    /// because of a different naming scheme or name suffix.
+    pub(crate) redirects: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ImageOptions {
+    /// Downscale images to their displayed size.
+    pub(crate) resize: bool,
+
+    /// Display downscaled thumbnails linking to the original image.
+    pub(crate) thumbnails: bool,
+
+    /// The format images are re-encoded to.
+    pub(crate) format: ImageFormat,
+
+    /// The JPEG encoding quality.
+    pub(crate) quality: u8,
+}
+
+impl ImageOptions {
+    /// Returns whether any image processing has been requested.
+    pub(crate) fn enabled(&self) -> bool {
+        self.resize || self.thumbnails || self.format != ImageFormat::Original
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FontOptions {
+    /// Add substitute fonts as fallbacks to font families.
+    pub(crate) substitute: bool,
+
+    /// The fonts and their substitutes.
+    pub(crate) map: FontMap,
+
+    /// The directory substitute font files are bundled from.
+    pub(crate) dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Filter {
+    /// Glob patterns of the section paths to render.
+    pub(crate) include_sections: Vec<Pattern>,
+
+    /// Glob patterns of the section paths to skip.
+    pub(crate) exclude_sections: Vec<Pattern>,
+
+    /// Patterns of the page titles to render.
+    pub(crate) include_pages: Vec<Regex>,
+
+    /// Patterns of the page titles to skip.
+    pub(crate) exclude_pages: Vec<Regex>,
+}
+
+impl Filter {
+    /// Returns whether the section with the given path is rendered.
+    ///
+    /// The path consists of the notebook name, the section group name and
+    /// the section name, separated by `/`.
+    pub(crate) fn includes_section(&self, path: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        let matches = |pattern: &Pattern| pattern.matches_with(path, options);
+
+        (self.include_sections.is_empty() || self.include_sections.iter().any(matches))
+            && !self.exclude_sections.iter().any(matches)
+    }
+
+    /// Returns whether the page with the given title is rendered.
+    pub(crate) fn includes_page(&self, title: &str) -> bool {
+        let matches = |regex: &Regex| regex.is_match(title);
+
+        (self.include_pages.is_empty() || self.include_pages.iter().any(matches))
+            && !self.exclude_pages.iter().any(matches)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ImageFormat {
+    /// Keep the format stored in the notebook
+    Original,
+    /// Lossless WebP
+    Webp,
+    /// JPEG with the configured quality
+    Jpeg,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Theme {
+    /// Dark text on a light background
+    Light,
+    /// Light text on a dark background
+    Dark,
+    /// Follow the color scheme preferred by the browser
+    Auto,
+}
+
+impl Theme {
+    /// The value of the CSS `color-scheme` property.
+    pub(crate) fn color_scheme(&self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+            Theme::Auto => "light dark",
+        }
+    }
+
+    pub(crate) fn is_auto(&self) -> bool {
+        *self == Theme::Auto
+    }
+
+    /// Returns whether the theme includes dark colors.
+    pub(crate) fn has_dark(&self) -> bool {
+        *self != Theme::Light
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum PageNames {
+    /// The page title
+    Title,
+    /// The page title as lowercase ASCII with dashes instead of spaces
+    Slug,
+    /// The position of the page in its section followed by the page title
+    Ordered,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub(crate) enum RecycleBin {
+    /// Don't render deleted sections and pages
+    Exclude,
+    /// Render deleted sections and pages in a "Recycle Bin" section group
+    Include,
+    /// Render deleted sections and pages with their own table of contents
+    Separate,
+}
+
+impl From<&RenderOpt> for Options {
+    fn from(opt: &RenderOpt) -> Self {
+        Options {
+            accessible: opt.accessible,
+            lang: opt.lang.clone(),
+            dedup_assets: opt.dedup_assets,
+            code_blocks: opt.code_blocks,
+            table_headers: opt.table_headers,
+            sortable_tables: opt.sortable_tables,
+            export_tables_csv: opt.export_tables,
+            export_tables_xlsx: opt.export_tables_xlsx,
+            images: ImageOptions {
+                resize: opt.resize_images,
+                thumbnails: opt.thumbnails,
+                format: opt.image_format,
+                quality: opt.image_quality,
+            },
+            fonts: FontOptions {
+                substitute: opt.substitute_fonts
+                    || opt.font_map.is_some()
+                    || opt.font_dir.is_some(),
+                map: FontMap::default(),
+                dir: opt.font_dir.clone(),
+            },
+            theme: opt.theme,
+            recycle_bin: opt.recycle_bin,
+            filter: Filter {
+                include_sections: opt.include_sections.clone(),
+                exclude_sections: opt.exclude_sections.clone(),
+                include_pages: opt.include_pages.clone(),
+                exclude_pages: opt.exclude_pages.clone(),
+            },
+            page_names: opt.page_names,
+            redirects: opt.redirects,
+        }
+    }
+}
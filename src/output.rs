@@ -0,0 +1,298 @@
+use color_eyre::eyre::{Result, WrapErr, eyre};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use itertools::Itertools;
+use log::warn;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+/// Where the converted files are written to.
+///
+/// When writing to an archive, paths are relative to the root of the archive.
+#[derive(Clone)]
+pub(crate) enum Sink {
+    /// Write files to the file system.
+    Directory,
+
+    /// Stream files into a ZIP archive or a gzip-compressed tarball.
+    Archive(Rc<RefCell<Archive>>),
+}
+
+pub(crate) struct Archive {
+    writer: ArchiveWriter,
+
+    /// The paths written to the archive so far.
+    entries: HashSet<String>,
+
+    /// Declared after the writer, so the file is closed before it is removed.
+    file: PartialFile,
+}
+
+enum ArchiveWriter {
+    Zip(Box<ZipWriter<File>>),
+    TarGz(Box<tar::Builder<GzEncoder<File>>>),
+}
+
+impl Sink {
+    /// Returns a sink writing into an archive if `output` is a `.zip`,
+    /// `.tar.gz` or `.tgz` file and a sink writing to the file system
+    /// otherwise.
+    ///
+    /// The archive is written to a temporary file next to `output` that is
+    /// moved to `output` by [`Sink::finish`] and removed otherwise.
+    pub(crate) fn new(output: &Path) -> Result<Self> {
+        let Some(format) = archive_format(output) else {
+            return Ok(Sink::Directory);
+        };
+
+        let (file, partial) = PartialFile::create(output)?;
+
+        let writer = match format {
+            ArchiveFormat::Zip => ArchiveWriter::Zip(Box::new(ZipWriter::new(file))),
+            ArchiveFormat::TarGz => ArchiveWriter::TarGz(Box::new(tar::Builder::new(
+                GzEncoder::new(file, Compression::default()),
+            ))),
+        };
+
+        Ok(Sink::Archive(Rc::new(RefCell::new(Archive {
+            writer,
+            entries: HashSet::new(),
+            file: partial,
+        }))))
+    }
+
+    /// Write a file, creating its parent directories if necessary.
+    pub(crate) fn write(&self, path: &Path, data: impl AsRef<[u8]>) -> Result<()> {
+        match self {
+            Sink::Directory => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                fs::write(path, data)?;
+            }
+            Sink::Archive(archive) => archive.borrow_mut().append(path, data.as_ref())?,
+        }
+
+        Ok(())
+    }
+
+    /// Create a directory. Archives store directories implicitly, so this
+    /// only has an effect on the file system.
+    pub(crate) fn create_dir(&self, path: &Path) -> Result<()> {
+        if let Sink::Directory = self {
+            fs::create_dir_all(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Copy a file from the file system into the output.
+    pub(crate) fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        match self {
+            Sink::Directory => {
+                if let Some(parent) = to.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                fs::copy(from, to)?;
+            }
+            Sink::Archive(_) => self.write(to, fs::read(from)?)?,
+        }
+
+        Ok(())
+    }
+
+    /// Remove a file written by a previous conversion. Archives are always
+    /// created from scratch, so there are no previous files to remove.
+    pub(crate) fn remove(&self, path: &Path) -> Result<()> {
+        if let Sink::Directory = self {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != ErrorKind::NotFound => {
+                    return Err(e).wrap_err_with(|| format!("Failed to remove {}", path.display()));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether a file has already been written.
+    pub(crate) fn exists(&self, path: &Path) -> bool {
+        match self {
+            Sink::Directory => path.is_file(),
+            Sink::Archive(archive) => archive.borrow().entries.contains(&entry_name(path)),
+        }
+    }
+
+    /// Read a file written by a previous conversion. Archives are always
+    /// created from scratch, so there are no previous files to read.
+    pub(crate) fn read_to_string(&self, path: &Path) -> Option<String> {
+        match self {
+            Sink::Directory => fs::read_to_string(path).ok(),
+            Sink::Archive(_) => None,
+        }
+    }
+
+    /// Finish writing the archive.
+    pub(crate) fn finish(self) -> Result<()> {
+        let Sink::Archive(archive) = self else {
+            return Ok(());
+        };
+
+        let archive = Rc::try_unwrap(archive)
+            .map_err(|_| eyre!("Archive is still in use"))?
+            .into_inner();
+
+        let Archive { writer, file, .. } = archive;
+
+        match writer {
+            ArchiveWriter::Zip(writer) => {
+                writer.finish().wrap_err("Failed to finish ZIP archive")?;
+            }
+            ArchiveWriter::TarGz(builder) => {
+                builder
+                    .into_inner()
+                    .and_then(GzEncoder::finish)
+                    .wrap_err("Failed to finish tarball")?;
+            }
+        }
+
+        file.persist()
+    }
+}
+
+impl Archive {
+    fn append(&mut self, path: &Path, data: &[u8]) -> Result<()> {
+        let name = entry_name(path);
+
+        // Entries can't be replaced once they have been streamed
+        if !self.entries.insert(name.clone()) {
+            warn!(
+                "Skipping file {} that was already written to the archive",
+                name
+            );
+
+            return Ok(());
+        }
+
+        match &mut self.writer {
+            ArchiveWriter::Zip(writer) => {
+                let options = SimpleFileOptions::default()
+                    .last_modified_time(zip::DateTime::default_for_write())
+                    .unix_permissions(0o644)
+                    .large_file(data.len() as u64 >= u32::MAX as u64);
+
+                writer.start_file(&name, options)?;
+                writer.write_all(data)?;
+            }
+            ArchiveWriter::TarGz(builder) => {
+                let mtime = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map(|time| time.as_secs())
+                    .unwrap_or_default();
+
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(mtime);
+
+                builder.append_data(&mut header, &name, data)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+/// Returns whether the output is written into an archive instead of a
+/// directory.
+pub(crate) fn is_archive(output: &Path) -> bool {
+    archive_format(output).is_some()
+}
+
+fn archive_format(output: &Path) -> Option<ArchiveFormat> {
+    let name = output
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase();
+
+    if name.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else {
+        None
+    }
+}
+
+/// The temporary file an archive is written to until it is finished.
+///
+/// Removed when dropped before [`PartialFile::persist`] has been called,
+/// e.g. because the conversion failed.
+struct PartialFile {
+    path: PathBuf,
+    target: PathBuf,
+    persisted: bool,
+}
+
+impl PartialFile {
+    fn create(target: &Path) -> Result<(File, Self)> {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(".part");
+
+        let path = target.with_file_name(name);
+        let file =
+            File::create(&path).wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+
+        Ok((
+            file,
+            PartialFile {
+                path,
+                target: target.to_path_buf(),
+                persisted: false,
+            },
+        ))
+    }
+
+    /// Move the file to its target path.
+    fn persist(mut self) -> Result<()> {
+        fs::rename(&self.path, &self.target)
+            .wrap_err_with(|| format!("Failed to create {}", self.target.display()))?;
+        self.persisted = true;
+
+        Ok(())
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Returns the name of a file in an archive, using `/` as separator.
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .join("/")
+}
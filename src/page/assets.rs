@@ -0,0 +1,51 @@
+use crate::page::Renderer;
+use crate::utils::relative_url;
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use sha2::{Digest, Sha256};
+
+impl<'a> Renderer<'a> {
+    /// Write a file referenced by the page and return the URL to reference it by.
+    ///
+    /// If asset deduplication is enabled the file is stored under its content
+    /// hash in the shared assets directory. Otherwise the file is written next
+    /// to the page using the name returned by `filename`.
+    pub(crate) fn write_file<F>(
+        &mut self,
+        data: &[u8],
+        extension: Option<&str>,
+        filename: F,
+    ) -> Result<String>
+    where
+        F: FnOnce(&mut Self) -> Result<String>,
+    {
+        let Some(assets_dir) = self.section.assets_dir.clone() else {
+            let filename = filename(self)?;
+            self.section
+                .sink
+                .write(&self.output.join(&filename), data)
+                .wrap_err("Failed to write file")?;
+
+            return Ok(filename);
+        };
+
+        let hash = format!("{:x}", Sha256::digest(data));
+        let filename = match extension.map(|ext| ext.trim_start_matches('.')) {
+            Some(ext) if !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                format!("{}.{}", hash, ext.to_ascii_lowercase())
+            }
+            _ => hash,
+        };
+
+        let path = assets_dir.join(&filename);
+
+        if !self.section.sink.exists(&path) {
+            self.section
+                .sink
+                .write(&path, data)
+                .wrap_err("Failed to write asset")?;
+        }
+
+        Ok(relative_url(&self.output, &path))
+    }
+}
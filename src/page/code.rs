@@ -0,0 +1,350 @@
+use crate::page::Renderer;
+use crate::page::text::rich_text_plain;
+use crate::utils::{StyleSet, escape_html, px};
+use onenote_parser::contents::{Content, OutlineElement, RichText};
+
+const MONOSPACE_FONTS: &[&str] = &[
+    "consolas",
+    "courier",
+    "courier new",
+    "lucida console",
+    "lucida sans typewriter",
+    "menlo",
+    "monaco",
+    "cascadia code",
+    "cascadia mono",
+    "source code pro",
+    "fira code",
+    "fira mono",
+    "dejavu sans mono",
+    "liberation mono",
+    "andale mono",
+];
+
+impl<'a> Renderer<'a> {
+    /// Returns whether the outline element is a line of monospace text that
+    /// should be merged into a code block.
+    pub(crate) fn is_code_line(&self, element: &OutlineElement) -> bool {
+        if !self.section.options.code_blocks
+            || self.is_list(element)
+            || !element.children().is_empty()
+        {
+            return false;
+        }
+
+        match element.contents() {
+            [Content::RichText(text)] => is_monospace(text),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the outline element is an empty paragraph that may be
+    /// part of a code block if more monospace lines follow it.
+    pub(crate) fn is_blank_code_line(&self, element: &OutlineElement) -> bool {
+        if !self.section.options.code_blocks
+            || self.is_list(element)
+            || !element.children().is_empty()
+        {
+            return false;
+        }
+
+        match element.contents() {
+            [Content::RichText(text)] => is_blank_monospace(text),
+            _ => false,
+        }
+    }
+
+    /// Render consecutive monospace lines as a highlighted code block.
+    pub(crate) fn render_code_block(
+        &mut self,
+        lines: &[(&OutlineElement, u8, u8)],
+        indents: &[f32],
+    ) -> String {
+        let texts = lines
+            .iter()
+            .map(|(element, _, level)| {
+                let text = element
+                    .contents()
+                    .iter()
+                    .filter_map(|content| content.rich_text())
+                    .map(rich_text_plain)
+                    .collect::<String>();
+
+                (text, *level)
+            })
+            .collect::<Vec<_>>();
+
+        // Blank lines don't count towards the indentation of the block
+        let base_level = texts
+            .iter()
+            .filter(|(text, _)| !text.trim().is_empty())
+            .map(|(_, level)| *level)
+            .min()
+            .unwrap_or(0);
+        let parent_level = lines.first().map(|(_, parent, _)| *parent).unwrap_or(0);
+
+        let code = texts
+            .iter()
+            .map(|(text, level)| {
+                if text.trim().is_empty() {
+                    String::new()
+                } else {
+                    "    ".repeat(level.saturating_sub(base_level) as usize) + text
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut indent_width = 0.0;
+        for i in (parent_level + 1)..=base_level {
+            indent_width += indents.get(i as usize).copied().unwrap_or(0.75);
+        }
+
+        let mut styles = StyleSet::new();
+        styles.set("margin-left", px(indent_width));
+
+        let language = detect_language(&code);
+        let class = language
+            .map(|lang| format!(" class=\"language-{}\" data-language=\"{}\"", lang, lang))
+            .unwrap_or_default();
+
+        format!(
+            "<pre class=\"code-block\" style=\"{}\"><code{}>{}</code></pre>\n",
+            styles,
+            class,
+            highlight(&code, language)
+        )
+    }
+}
+
+fn is_monospace_font(font: &str) -> bool {
+    MONOSPACE_FONTS.contains(&font.trim().to_ascii_lowercase().as_str())
+}
+
+fn is_monospace(text: &RichText) -> bool {
+    if !text.embedded_objects().is_empty() || text.text().trim().is_empty() {
+        return false;
+    }
+
+    let paragraph_font = text.paragraph_style().font();
+    let runs = text.text_run_formatting();
+
+    if runs.is_empty() {
+        return paragraph_font.is_some_and(is_monospace_font);
+    }
+
+    runs.iter()
+        .all(|run| run.font().or(paragraph_font).is_some_and(is_monospace_font))
+}
+
+fn is_blank_monospace(text: &RichText) -> bool {
+    if !text.embedded_objects().is_empty() || !text.text().trim().is_empty() {
+        return false;
+    }
+
+    // Empty paragraphs often carry no font at all
+    let paragraph_font = text.paragraph_style().font();
+    text.text_run_formatting()
+        .iter()
+        .filter_map(|run| run.font())
+        .chain(paragraph_font)
+        .all(is_monospace_font)
+}
+
+/// Guess the programming language of a code snippet.
+pub(crate) fn detect_language(code: &str) -> Option<&'static str> {
+    let trimmed = code.trim_start();
+    let contains_any = |patterns: &[&str]| patterns.iter().any(|p| code.contains(p));
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('[')) && code.contains("\":") {
+        return Some("json");
+    }
+
+    if trimmed.starts_with("#!") && contains_any(&["sh", "bash", "zsh"]) {
+        return Some("shell");
+    }
+
+    if trimmed.starts_with('<') {
+        return Some("xml");
+    }
+
+    if contains_any(&[
+        "fn ",
+        "let mut ",
+        "impl ",
+        "pub struct ",
+        "use std::",
+        "println!(",
+    ]) {
+        return Some("rust");
+    }
+
+    if contains_any(&["#include", "std::", "printf("]) {
+        return Some("cpp");
+    }
+
+    if contains_any(&["public class ", "public static void", "System.out."]) {
+        return Some("java");
+    }
+
+    if contains_any(&["def ", "elif ", "import ", "self.", "print("]) && !code.contains(';') {
+        return Some("python");
+    }
+
+    if contains_any(&["function ", "const ", "=> {", "console.log", "let "]) {
+        return Some("javascript");
+    }
+
+    let upper = code.to_ascii_uppercase();
+    if (upper.contains("SELECT ") && upper.contains(" FROM "))
+        || upper.contains("INSERT INTO ")
+        || upper.contains("CREATE TABLE ")
+    {
+        return Some("sql");
+    }
+
+    if contains_any(&["echo ", "sudo ", "export ", "$ ", "| grep"]) {
+        return Some("shell");
+    }
+
+    None
+}
+
+fn keywords(language: &str) -> &'static str {
+    match language {
+        "rust" => {
+            "as async await break const continue crate dyn else enum false fn for if impl in \
+             let loop match mod move mut pub ref return self Self static struct super trait \
+             true type unsafe use where while"
+        }
+        "python" => {
+            "and as assert async await break class continue def del elif else except False \
+             finally for from global if import in is lambda None nonlocal not or pass raise \
+             return True try while with yield"
+        }
+        "javascript" => {
+            "async await break case catch class const continue default delete do else export \
+             extends false finally for function if import in instanceof let new null return \
+             switch this throw true try typeof undefined var void while yield"
+        }
+        "cpp" | "java" => {
+            "auto break case catch char class const continue default delete do double else \
+             enum extends false final float for if implements import int long namespace new \
+             null nullptr package private protected public return short static struct switch \
+             template this throw true try typedef unsigned using virtual void while"
+        }
+        "sql" => {
+            "AND AS BY CREATE DELETE DESC DISTINCT DROP FROM GROUP HAVING INSERT INTO JOIN \
+             LEFT LIMIT NOT NULL ON OR ORDER SELECT SET TABLE UPDATE VALUES WHERE"
+        }
+        "shell" => {
+            "case do done echo elif else esac export fi for function if in local return then \
+             while"
+        }
+        "json" => "true false null",
+        _ => "",
+    }
+}
+
+fn line_comment(language: &str) -> Option<&'static str> {
+    match language {
+        "rust" | "javascript" | "cpp" | "java" => Some("//"),
+        "python" | "shell" => Some("#"),
+        "sql" => Some("--"),
+        _ => None,
+    }
+}
+
+/// Highlight a code snippet, returning escaped HTML.
+pub(crate) fn highlight(code: &str, language: Option<&str>) -> String {
+    let Some(language) = language.filter(|lang| *lang != "xml") else {
+        return escape_html(code);
+    };
+
+    let keywords = keywords(language);
+    let comment = line_comment(language);
+    let case_insensitive = language == "sql";
+
+    let chars: Vec<char> = code.chars().collect();
+    let mut output = String::new();
+    let mut i = 0;
+
+    let span = |class: &str, text: &str| {
+        format!("<span class=\"hl-{}\">{}</span>", class, escape_html(text))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+
+        if let Some(comment) = comment
+            && rest.starts_with(comment)
+        {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == '\n')
+                .map_or(chars.len(), |pos| i + pos);
+            let text: String = chars[i..end].iter().collect();
+            output.push_str(&span("comment", &text));
+            i = end;
+        } else if c == '\'' && language == "rust" && !is_char_literal(&chars[i..]) {
+            // Lifetime annotation
+            output.push(c);
+            i += 1;
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut end = i + 1;
+
+            while end < chars.len() && chars[end] != c && chars[end] != '\n' {
+                if chars[end] == '\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+
+            let end = (end + 1).min(chars.len());
+            let text: String = chars[i..end].iter().collect();
+            output.push_str(&span("string", &text));
+            i = end;
+        } else if c.is_ascii_digit() {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !(c.is_ascii_alphanumeric() || *c == '.' || *c == '_'))
+                .map_or(chars.len(), |pos| i + pos);
+            let text: String = chars[i..end].iter().collect();
+            output.push_str(&span("number", &text));
+            i = end;
+        } else if c.is_alphabetic() || c == '_' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                .map_or(chars.len(), |pos| i + pos);
+            let word: String = chars[i..end].iter().collect();
+
+            let candidate = if case_insensitive {
+                word.to_ascii_uppercase()
+            } else {
+                word.clone()
+            };
+            let is_keyword = keywords
+                .split_whitespace()
+                .any(|keyword| keyword == candidate);
+
+            if is_keyword {
+                output.push_str(&span("keyword", &word));
+            } else {
+                output.push_str(&escape_html(&word));
+            }
+
+            i = end;
+        } else {
+            output.push_str(&escape_html(&c.to_string()));
+            i += 1;
+        }
+    }
+
+    output
+}
+
+fn is_char_literal(chars: &[char]) -> bool {
+    matches!(chars, ['\'', '\\', ..] | ['\'', _, '\'', ..])
+}
@@ -0,0 +1,49 @@
+use crate::options::Theme;
+use crate::page::Renderer;
+use palette::{Clamp, FromColor, Lch, Srgb};
+
+/// How a color is used, which determines how it is adjusted for dark themes.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum ColorRole {
+    /// Text and strokes drawn on top of the page background
+    Foreground,
+    /// Highlights and cell shading drawn behind text
+    Background,
+}
+
+impl<'a> Renderer<'a> {
+    /// Returns the CSS value of an explicit color for the configured theme.
+    pub(crate) fn css_color(&self, (r, g, b): (u8, u8, u8), role: ColorRole) -> String {
+        let light = format!("rgb({},{},{})", r, g, b);
+
+        let dark = || {
+            let (r, g, b) = dark_color((r, g, b), role);
+            format!("rgb({},{},{})", r, g, b)
+        };
+
+        match self.section.options.theme {
+            Theme::Light => light,
+            Theme::Dark => dark(),
+            Theme::Auto => format!("light-dark({}, {})", light, dark()),
+        }
+    }
+}
+
+/// Adjust a color for display on a dark background.
+///
+/// The lightness is mirrored while hue and chroma are kept, so dark text turns
+/// light and bright highlights turn dark without losing their color.
+fn dark_color((r, g, b): (u8, u8, u8), role: ColorRole) -> (u8, u8, u8) {
+    let mut color = Lch::from_color(Srgb::new(r, g, b).into_format::<f32>());
+
+    match role {
+        ColorRole::Foreground if color.l < 50.0 => color.l = 100.0 - color.l,
+        ColorRole::Background if color.l > 50.0 => color.l = (100.0 - color.l) * 0.5 + 15.0,
+        _ => {}
+    }
+
+    Srgb::from_color(color)
+        .clamp()
+        .into_format()
+        .into_components()
+}
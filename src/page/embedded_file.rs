@@ -1,29 +1,155 @@
 use crate::page::Renderer;
+use crate::page::note_tag::{ICON_BOOK, ICON_FILM, ICON_MUSIC, ICON_PAPER};
 use crate::utils::sanitize_output_filename;
+use crate::utils::{AttributeSet, StyleSet, escape_html, format_size, px};
 use color_eyre::Result;
-use color_eyre::eyre::{ContextCompat, WrapErr};
-use onenote_parser::contents::EmbeddedFile;
+use color_eyre::eyre::ContextCompat;
+use itertools::Itertools;
+use mime_guess::Mime;
+use onenote_parser::contents::{Content, EmbeddedFile, OutlineElement, OutlineItem};
+use onenote_parser::page::{Page, PageContent};
 use onenote_parser::property::embedded_file::FileType;
-use std::fs;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+const ICON_FILE: &str = include_str!("../../assets/icons/file-line.svg");
+
+/// An embedded file that has been written for the page.
+pub(crate) struct WrittenFile {
+    /// The original file name.
+    pub(crate) name: String,
+
+    /// The SHA-256 hash of the file contents.
+    hash: String,
+
+    pub(crate) url: String,
+}
 
 impl<'a> Renderer<'a> {
     pub(crate) fn render_embedded_file(&mut self, file: &EmbeddedFile) -> Result<String> {
-        let filename = self.determine_filename(file.filename())?;
-        fs::write(self.output.join(filename.clone()), file.data())
-            .wrap_err("Failed to write embedded file")?;
+        let filename = self.write_embedded_file(file)?;
 
         let file_type = Self::guess_type(file);
 
         let content = match file_type {
             FileType::Audio => format!("<audio controls src=\"{}\"></audio>", filename),
             FileType::Video => format!("<video controls src=\"{}\"></video>", filename),
-            FileType::Unknown => format!("<embed src=\"{}\" />", filename),
+            FileType::Unknown => self.render_file_card(file, &filename),
         };
 
         Ok(self.render_with_note_tags(file.note_tags(), content))
     }
 
+    /// Write an embedded file and return its URL. Files that have already been
+    /// written for the current page aren't written again.
+    pub(crate) fn write_embedded_file(&mut self, file: &EmbeddedFile) -> Result<String> {
+        if let Some(url) = self.embedded_file_url(file) {
+            return Ok(url.to_string());
+        }
+
+        let extension = Path::new(file.filename())
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_string());
+        let filename = self.write_file(file.data(), extension.as_deref(), |this| {
+            this.determine_filename(file.filename())
+        })?;
+
+        self.embedded_files.push(WrittenFile {
+            name: file.filename().to_string(),
+            hash: content_hash(file.data()),
+            url: filename.clone(),
+        });
+
+        Ok(filename)
+    }
+
+    /// Returns the URL of an embedded file that has been written for the page.
+    pub(crate) fn embedded_file_url(&self, file: &EmbeddedFile) -> Option<&str> {
+        let hash = content_hash(file.data());
+
+        self.embedded_files
+            .iter()
+            .find(|written| written.name == file.filename() && written.hash == hash)
+            .map(|written| written.url.as_str())
+    }
+
+    fn render_file_card(&self, file: &EmbeddedFile, url: &str) -> String {
+        let mime = mime_guess::from_path(file.filename()).first();
+
+        let mut styles = StyleSet::new();
+
+        if file.offset_horizontal().is_some() || file.offset_vertical().is_some() {
+            styles.set("position", "absolute".to_string());
+        }
+
+        if let Some(offset) = file.offset_horizontal() {
+            styles.set("left", px(offset));
+        }
+
+        if let Some(offset) = file.offset_vertical() {
+            styles.set("top", px(offset));
+        }
+
+        let mut attrs = AttributeSet::new();
+        attrs.set("class", "file".to_string());
+
+        if styles.len() > 0 {
+            attrs.set("style", styles.to_string());
+        }
+
+        let mut content = format!("<div {}>", attrs);
+
+        // The layout size of embedded files is the size of their icon, so
+        // previews are sized by the stylesheet instead
+        if mime
+            .as_ref()
+            .is_some_and(|mime| mime.essence_str() == "application/pdf")
+        {
+            content.push_str(&format!(
+                "<object class=\"file-preview\" data=\"{}\" type=\"application/pdf\"></object>",
+                url
+            ));
+        } else if let Some(text) = text_preview(file, mime.as_ref()) {
+            content.push_str(&format!(
+                "<pre class=\"file-preview\">{}</pre>",
+                escape_html(&text)
+            ));
+        }
+
+        let icon = match mime
+            .as_ref()
+            .map(|mime| (mime.type_().as_str(), mime.subtype().as_str()))
+        {
+            Some(("application", "pdf")) => ICON_BOOK,
+            Some(("audio", _)) => ICON_MUSIC,
+            Some(("video", _)) => ICON_FILM,
+            Some(("text", _)) => ICON_PAPER,
+            Some(("application", subtype))
+                if subtype.contains("document")
+                    || subtype.contains("msword")
+                    || subtype.contains("sheet")
+                    || subtype.contains("excel")
+                    || subtype.contains("presentation")
+                    || subtype.contains("powerpoint") =>
+            {
+                ICON_PAPER
+            }
+            _ => ICON_FILE,
+        };
+
+        content.push_str(&format!(
+            "<div class=\"file-card\"><span class=\"file-card-icon\">{}</span><span class=\"file-card-details\"><a class=\"file-card-name\" href=\"{}\" download>{}</a><span class=\"file-card-size\">{}</span></span></div>",
+            icon,
+            url,
+            escape_html(file.filename()),
+            format_size(file.data().len())
+        ));
+
+        content.push_str("</div>");
+
+        content
+    }
+
     fn guess_type(file: &EmbeddedFile) -> FileType {
         match file.file_type() {
             FileType::Audio => return FileType::Audio,
@@ -77,3 +203,86 @@ impl<'a> Renderer<'a> {
         }
     }
 }
+
+fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Returns all embedded files of a page, including the files in outlines
+/// and tables.
+pub(crate) fn page_embedded_files(page: &Page) -> Vec<&EmbeddedFile> {
+    let mut files = Vec::new();
+
+    for content in page.contents() {
+        match content {
+            PageContent::EmbeddedFile(file) => files.push(file),
+            PageContent::Outline(outline) => outline_embedded_files(outline.items(), &mut files),
+            _ => {}
+        }
+    }
+
+    files
+}
+
+fn outline_embedded_files<'b>(items: &'b [OutlineItem], files: &mut Vec<&'b EmbeddedFile>) {
+    for item in items {
+        match item {
+            OutlineItem::Group(group) => outline_embedded_files(group.outlines(), files),
+            OutlineItem::Element(element) => element_embedded_files(element, files),
+        }
+    }
+}
+
+fn element_embedded_files<'b>(element: &'b OutlineElement, files: &mut Vec<&'b EmbeddedFile>) {
+    for content in element.contents() {
+        match content {
+            Content::EmbeddedFile(file) => files.push(file),
+            Content::Table(table) => {
+                for cell in table.contents().iter().flat_map(|row| row.contents()) {
+                    for element in cell.contents() {
+                        element_embedded_files(element, files);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    outline_embedded_files(element.children(), files);
+}
+
+/// Return the start of the file contents if the file is a text file.
+fn text_preview(file: &EmbeddedFile, mime: Option<&Mime>) -> Option<String> {
+    const TEXT_EXTENSIONS: &[&str] = &[
+        "c", "cfg", "conf", "cpp", "cs", "css", "csv", "go", "h", "hpp", "ini", "java", "js",
+        "json", "kt", "log", "md", "php", "pl", "ps1", "py", "rb", "rs", "sh", "sql", "swift",
+        "toml", "ts", "tsv", "txt", "xml", "yaml", "yml",
+    ];
+    const MAX_PREVIEW_LINES: usize = 200;
+
+    let extension = Path::new(file.filename())
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+
+    let is_text = extension
+        .as_deref()
+        .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext))
+        || mime.is_some_and(|mime| {
+            mime.type_() == "text"
+                || matches!(mime.subtype().as_str(), "json" | "xml" | "javascript")
+        });
+
+    if !is_text {
+        return None;
+    }
+
+    let text = std::str::from_utf8(file.data()).ok()?;
+    let mut lines = text.lines();
+    let mut preview = lines.by_ref().take(MAX_PREVIEW_LINES).join("\n");
+
+    if lines.next().is_some() {
+        preview.push_str("\n…");
+    }
+
+    Some(preview)
+}
@@ -0,0 +1,70 @@
+use crate::font_map::find_font_files;
+use crate::page::Renderer;
+use crate::utils::relative_url;
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+
+impl<'a> Renderer<'a> {
+    /// Returns the CSS font family for a font, with its substitute as a fallback.
+    pub(crate) fn font_family(&mut self, font: &str) -> String {
+        let fonts = &self.section.options.fonts;
+
+        if !fonts.substitute {
+            return font.to_string();
+        }
+
+        let font = font.trim();
+        let family = match fonts.map.substitute(font) {
+            Some(substitute) => format!("{}, {}", font, substitute),
+            None => font.to_string(),
+        };
+
+        self.fonts.insert(font.to_string());
+
+        family
+    }
+
+    /// Copy the substitute fonts used on the page to the fonts directory and
+    /// return the `@font-face` rules referencing them.
+    pub(crate) fn render_font_faces(&mut self) -> Result<String> {
+        let options = &self.section.options.fonts;
+
+        let (Some(source_dir), Some(fonts_dir)) = (&options.dir, &self.section.fonts_dir) else {
+            return Ok(String::new());
+        };
+
+        let families = self
+            .fonts
+            .iter()
+            .filter_map(|font| options.map.substitute(font))
+            .unique();
+
+        let mut rules = String::new();
+
+        for family in families {
+            for file in find_font_files(source_dir, family)? {
+                let target = fonts_dir.join(file.path.file_name().unwrap_or_default());
+
+                if !self.section.sink.exists(&target) {
+                    self.section
+                        .sink
+                        .copy(&file.path, &target)
+                        .wrap_err("Failed to copy font file")?;
+                }
+
+                rules.push_str(&format!(
+                    "@font-face {{ font-family: \"{}\"; src: local(\"{}\"), url(\"{}\") format(\"{}\"); font-weight: {}; font-style: {}; }}\n",
+                    family,
+                    family,
+                    relative_url(&self.output, &target),
+                    file.format(),
+                    file.weight,
+                    if file.italic { "italic" } else { "normal" },
+                ));
+            }
+        }
+
+        Ok(rules)
+    }
+}
@@ -1,27 +1,41 @@
+use crate::image_processing::{is_metafile, process_image};
 use crate::page::Renderer;
 use crate::utils::sanitize_output_filename;
-use crate::utils::{AttributeSet, StyleSet, px};
+use crate::utils::{AttributeSet, StyleSet, escape_html, px};
 use color_eyre::Result;
-use color_eyre::eyre::WrapErr;
+use log::warn;
 use onenote_parser::contents::Image;
-use std::fs;
+use std::path::Path;
 
 impl<'a> Renderer<'a> {
     pub(crate) fn render_image(&mut self, image: &Image) -> Result<String> {
         let mut content = String::new();
 
         if let Some(data) = image.data() {
-            let filename = self.determine_image_filename(image)?;
-            fs::write(self.output.join(filename.clone()), data)
-                .wrap_err("Failed to write image")?;
+            let options = &self.section.options.images;
+
+            if options.enabled() && image.extension().is_some_and(is_metafile) {
+                let filename = self.write_file(data, image.extension(), |this| {
+                    this.determine_image_filename(image, None)
+                })?;
+
+                content.push_str(&self.render_image_placeholder(image, &filename));
+
+                return Ok(self.render_with_note_tags(image.note_tags(), content));
+            }
+
+            let (filename, thumbnail) = self.write_image(image, data)?;
 
             let mut attrs = AttributeSet::new();
             let mut styles = StyleSet::new();
 
-            attrs.set("src", filename);
+            attrs.set("src", thumbnail.clone().unwrap_or_else(|| filename.clone()));
 
             if let Some(text) = image.alt_text() {
                 attrs.set("alt", text.to_string().replace('"', "&quot;"));
+            } else if self.section.options.accessible {
+                let alt = image.text().or(image.image_filename()).unwrap_or("Image");
+                attrs.set("alt", escape_html(alt));
             }
 
             if let Some(width) = image.layout_max_width() {
@@ -48,19 +62,118 @@ impl<'a> Renderer<'a> {
                 attrs.set("style", styles.to_string());
             }
 
-            content.push_str(&format!("<img {} />", attrs));
+            if thumbnail.is_some() {
+                content.push_str(&format!("<a href=\"{}\"><img {} /></a>", filename, attrs));
+            } else {
+                content.push_str(&format!("<img {} />", attrs));
+            }
         }
 
         Ok(self.render_with_note_tags(image.note_tags(), content))
     }
 
-    fn determine_image_filename(&mut self, image: &Image) -> Result<String> {
+    /// Write the image data (and its thumbnail, if enabled) to the output.
+    ///
+    /// Returns the URL of the image and of its thumbnail.
+    pub(crate) fn write_image(
+        &mut self,
+        image: &Image,
+        data: &[u8],
+    ) -> Result<(String, Option<String>)> {
+        let options = &self.section.options.images;
+
+        let processed = if options.enabled() {
+            process_image(data, display_bounds(image), options)
+                .inspect_err(|err| warn!("Failed to process image, keeping original: {:?}", err))
+                .ok()
+        } else {
+            None
+        };
+
+        let files = match processed {
+            Some(processed) => {
+                let extension = processed.extension;
+                let filename = self.write_file(&processed.data, Some(extension), |this| {
+                    this.determine_image_filename(image, Some(extension))
+                })?;
+
+                let thumbnail = match processed.thumbnail {
+                    Some(data) => Some(self.write_file(&data, Some(extension), |this| {
+                        this.determine_filename(&thumbnail_filename(&filename))
+                    })?),
+                    None => None,
+                };
+
+                (filename, thumbnail)
+            }
+            None => {
+                let filename = self.write_file(data, image.extension(), |this| {
+                    this.determine_image_filename(image, None)
+                })?;
+
+                (filename, None)
+            }
+        };
+
+        Ok(files)
+    }
+
+    fn render_image_placeholder(&self, image: &Image, filename: &str) -> String {
+        let mut styles = StyleSet::new();
+
+        if let Some(width) = image.layout_max_width() {
+            styles.set("width", px(width));
+        }
+
+        if let Some(height) = image.layout_max_height() {
+            styles.set("height", px(height));
+        }
+
+        if image.offset_horizontal().is_some() || image.offset_vertical().is_some() {
+            styles.set("position", "absolute".to_string());
+        }
+
+        if let Some(offset) = image.offset_horizontal() {
+            styles.set("left", px(offset));
+        }
+
+        if let Some(offset) = image.offset_vertical() {
+            styles.set("top", px(offset));
+        }
+
+        let kind = image
+            .extension()
+            .unwrap_or_default()
+            .trim_start_matches('.')
+            .to_ascii_uppercase();
+
+        format!(
+            "<a class=\"image-placeholder\" href=\"{}\" style=\"{}\">{} image</a>",
+            filename, styles, kind
+        )
+    }
+
+    fn determine_image_filename(
+        &mut self,
+        image: &Image,
+        extension: Option<&str>,
+    ) -> Result<String> {
         if let Some(name) = image.image_filename() {
             let sanitized = sanitize_output_filename(name)?;
+            let sanitized = match extension {
+                Some(ext) => Path::new(&sanitized)
+                    .with_extension(ext)
+                    .to_string_lossy()
+                    .to_string(),
+                None => sanitized,
+            };
+
             return self.determine_filename(&sanitized);
         }
 
-        if let Some(ext) = image.extension() {
+        let extension = extension.map(|ext| format!(".{}", ext));
+
+        if let Some(ext) = extension.as_deref().or(image.extension()) {
             let mut i = 0;
 
             loop {
@@ -91,3 +204,28 @@ impl<'a> Renderer<'a> {
         }
     }
 }
+
+/// The maximum display size of an image in pixels.
+fn display_bounds(image: &Image) -> Option<(u32, u32)> {
+    let width = image
+        .layout_max_width()
+        .map(|width| (width * 48.0).round() as u32);
+    let height = image
+        .layout_max_height()
+        .map(|height| (height * 48.0).round() as u32);
+
+    match (width, height) {
+        (None, None) => None,
+        (width, height) => Some((width.unwrap_or(u32::MAX), height.unwrap_or(u32::MAX))),
+    }
+}
+
+fn thumbnail_filename(filename: &str) -> String {
+    let path = Path::new(filename);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    match path.extension() {
+        Some(ext) => format!("{}-thumb.{}", stem, ext.to_string_lossy()),
+        None => format!("{}-thumb", stem),
+    }
+}
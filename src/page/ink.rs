@@ -1,4 +1,5 @@
 use crate::page::Renderer;
+use crate::page::color::ColorRole;
 use crate::utils::{AttributeSet, StyleSet, px};
 use itertools::Itertools;
 use onenote_parser::contents::{Ink, InkBoundingBox, InkPoint, InkStroke};
@@ -96,6 +97,17 @@ impl<'a> Renderer<'a> {
             attrs.set("style", styles.to_string());
         }
 
+        if self.section.options.accessible {
+            let label = if embedded {
+                "Handwritten text"
+            } else {
+                "Ink drawing"
+            };
+
+            attrs.set("role", "img".to_string());
+            attrs.set("aria-label", label.to_string());
+        }
+
         if embedded {
             let mut span_styles = StyleSet::new();
 
@@ -139,7 +151,7 @@ impl<'a> Renderer<'a> {
             let rem = (rem - g) / 256;
             let b = rem % 256;
 
-            format!("rgb({}, {}, {})", r, g, b)
+            self.css_color((r as u8, g as u8, b as u8), ColorRole::Foreground)
         } else {
             "WindowText".to_string()
         };
@@ -1,6 +1,8 @@
 use crate::page::Renderer;
+use crate::page::color::ColorRole;
 use crate::utils::{AttributeSet, StyleSet, px};
 use color_eyre::Result;
+use itertools::Itertools;
 use log::warn;
 use onenote_parser::contents::{List, OutlineElement};
 use onenote_parser::property::common::ColorRef;
@@ -15,9 +17,72 @@ impl<'a> Renderer<'a> {
     ) -> Result<String> {
         let mut contents = String::new();
         let mut in_list = false;
-        let mut list_end = None;
+        let mut list_end: Option<String> = None;
+
+        let elements = elements.collect::<Vec<_>>();
+        let mut i = 0;
+
+        while i < elements.len() {
+            let (element, parent_level, current_level) = elements[i];
+            i += 1;
+
+            if in_list && self.is_code_line(element) {
+                contents.push_str(&list_end.take().expect("no list end tag defined"));
+                in_list = false;
+            }
+
+            if self.is_code_line(element) {
+                let start = i - 1;
+
+                // Keep blank lines inside the block if more code follows them
+                loop {
+                    let blank = elements[i..]
+                        .iter()
+                        .take_while(|(element, _, _)| self.is_blank_code_line(element))
+                        .count();
+
+                    match elements.get(i + blank) {
+                        Some((element, _, _)) if self.is_code_line(element) => i += blank + 1,
+                        _ => break,
+                    }
+                }
+
+                contents.push_str(&self.render_code_block(&elements[start..i], indents));
+                continue;
+            }
+
+            // Printouts inside outlines put each page into an element of its own
+            if self.is_printout_element(element) {
+                if in_list {
+                    contents.push_str(&list_end.take().expect("no list end tag defined"));
+                    in_list = false;
+                }
+
+                let start = i - 1;
+
+                while i < elements.len() && self.is_printout_element(elements[i].0) {
+                    i += 1;
+                }
+
+                let pages = elements[start..i]
+                    .iter()
+                    .flat_map(|(element, _, _)| element.contents())
+                    .filter_map(|content| content.image())
+                    .collect_vec();
+
+                // Embedded files in the elements right before or after the printout
+                let adjacent = [start.checked_sub(1), Some(i)]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| elements.get(i))
+                    .flat_map(|(element, _, _)| element.contents())
+                    .filter_map(|content| content.embedded_file())
+                    .collect_vec();
+
+                contents.push_str(&self.render_printout(&pages, &adjacent)?);
+                continue;
+            }
 
-        for (element, parent_level, current_level) in elements {
             if !in_list && self.is_list(element) {
                 let tags = self.list_tags(element);
                 let list_start = tags.0;
@@ -75,28 +140,13 @@ impl<'a> Renderer<'a> {
 
         self.fix_wingdings(&mut list_font, &mut list_format, &mut font_size);
 
-        match list_format {
-            [FORMAT_NUMBERED_LIST, '\u{0}', ..] => {}
-            [FORMAT_NUMBERED_LIST, '\u{1}', ..] => {
-                container_style.set("list-style-type", "upper-roman".to_string())
-            }
-            [FORMAT_NUMBERED_LIST, '\u{2}', ..] => {
-                container_style.set("list-style-type", "lower-roman".to_string())
-            }
-            [FORMAT_NUMBERED_LIST, '\u{3}', ..] => {
-                container_style.set("list-style-type", "upper-latin".to_string())
-            }
-            [FORMAT_NUMBERED_LIST, '\u{4}', ..] => {
-                container_style.set("list-style-type", "lower-latin".to_string())
-            }
-            [FORMAT_NUMBERED_LIST, c, ..] => {
-                warn!(
-                    "Unsupported numbered list format marker {}; falling back to default numbering",
-                    c
-                );
-            }
-            [c] => marker_style.set("content", format!("'{}'", c)),
-            _ => {}
+        if self.is_numbered_list(list) {
+            let (style, content) = numbered_marker(list_format);
+
+            container_style.set("list-style-type", style.to_string());
+            marker_style.set("content", content);
+        } else if let [c] = list_format {
+            marker_style.set("content", format!("'{}'", c));
         }
 
         let bullet_spacing = spacing.unwrap_or(0.2);
@@ -107,15 +157,15 @@ impl<'a> Renderer<'a> {
         container_style.set("left", px(-bullet_spacing));
 
         if let Some(font) = list_font {
-            marker_style.set("font-family", font.to_string());
+            marker_style.set("font-family", self.font_family(font));
         }
 
         if let Some(font) = list.font() {
-            marker_style.set("font-family", font.to_string());
+            marker_style.set("font-family", self.font_family(font));
         }
 
         if let Some(ColorRef::Manual { r, g, b }) = list.font_color() {
-            marker_style.set("color", format!("rgb({},{},{})", r, g, b));
+            marker_style.set("color", self.css_color((r, g, b), ColorRole::Foreground));
         }
 
         if let Some(size) = font_size {
@@ -151,35 +201,144 @@ impl<'a> Renderer<'a> {
         list_format: &mut &[char],
         font_size: &mut Option<u16>,
     ) {
-        match list_font.zip(list_format.first()) {
-            // See http://www.alanwood.net/demos/wingdings.html
-            Some(("Wingdings", '\u{a7}')) => *list_format = &['\u{25aa}'],
-            Some(("Wingdings", '\u{a8}')) => *list_format = &['\u{25fb}'],
-            Some(("Wingdings", '\u{77}')) => *list_format = &['\u{2b25}'],
-
-            // See http://www.alanwood.net/demos/wingdings-2.html
-            Some(("Wingdings 2", '\u{ae}')) => *list_format = &['\u{25c6}'],
-
-            // See http://www.alanwood.net/demos/wingdings-3.html
-            Some(("Wingdings 3", '\u{7d}')) => {
-                *list_format = &['\u{25b6}'];
-                *font_size = Some(18);
-            }
+        let (Some(font), [c]) = (*list_font, *list_format) else {
+            return;
+        };
+        let Some(bullet) = symbol_font_bullet(font, *c) else {
+            return;
+        };
 
-            _ => return,
+        // The Wingdings 3 arrow is drawn much larger than its Unicode counterpart
+        if bullet == ['\u{25b6}'] {
+            *font_size = Some(18);
         }
 
+        *list_format = bullet;
         *list_font = Some("Calibri");
     }
 
     fn is_numbered_list(&self, list: &List) -> bool {
-        list.list_format()
-            .first()
-            .map(|c| *c == FORMAT_NUMBERED_LIST)
-            .unwrap_or_default()
+        list.list_format().contains(&FORMAT_NUMBERED_LIST)
     }
 
     pub(crate) fn is_list(&self, element: &OutlineElement) -> bool {
         !element.list_contents().is_empty()
     }
 }
+
+/// Build the list style type and the `::marker` content of a numbered list.
+///
+/// The list format consists of literal text and number placeholders. Each
+/// placeholder is the `FORMAT_NUMBERED_LIST` character followed by the number
+/// format. Formats with multiple placeholders (e.g. `1.2.3`) render the
+/// numbers of all parent lists.
+fn numbered_marker(format: &[char]) -> (&'static str, String) {
+    let placeholders = format
+        .iter()
+        .filter(|c| **c == FORMAT_NUMBERED_LIST)
+        .count();
+
+    let first = format
+        .iter()
+        .position(|c| *c == FORMAT_NUMBERED_LIST)
+        .unwrap_or_default();
+    let last = format
+        .iter()
+        .rposition(|c| *c == FORMAT_NUMBERED_LIST)
+        .unwrap_or_default();
+
+    let style = number_format_style(format.get(first + 1).copied());
+    let prefix: String = format[..first].iter().collect();
+    let suffix: String = format.get(last + 2..).unwrap_or_default().iter().collect();
+
+    let counter = if placeholders > 1 {
+        format!("counters(list-item, \".\", {})", style)
+    } else {
+        format!("counter(list-item, {})", style)
+    };
+
+    let content = format!(
+        "\"{}\" {} \"{} \"",
+        escape_css_string(&prefix),
+        counter,
+        escape_css_string(&suffix)
+    );
+
+    (style, content)
+}
+
+/// Map an Office number format code to a CSS counter style.
+fn number_format_style(format: Option<char>) -> &'static str {
+    match format.map(|c| c as u32) {
+        None | Some(0) => "decimal",
+        Some(1) => "upper-roman",
+        Some(2) => "lower-roman",
+        Some(3) => "upper-latin",
+        Some(4) => "lower-latin",
+        Some(10) | Some(11) => "cjk-decimal",
+        Some(22) => "decimal-leading-zero",
+        Some(code) => {
+            warn!(
+                "Unsupported numbered list format {}; falling back to decimal numbering",
+                code
+            );
+
+            "decimal"
+        }
+    }
+}
+
+/// Map bullet characters of common symbol fonts to Unicode.
+fn symbol_font_bullet(font: &str, c: char) -> Option<&'static [char]> {
+    // Symbol fonts are often stored in the private use area at U+F0xx
+    let code = match c as u32 {
+        code @ 0xf020..=0xf0ff => code - 0xf000,
+        code => code,
+    };
+
+    let font = font.trim().to_ascii_lowercase();
+
+    // See http://www.alanwood.net/demos/symbol.html,
+    // http://www.alanwood.net/demos/wingdings.html,
+    // http://www.alanwood.net/demos/wingdings-2.html and
+    // http://www.alanwood.net/demos/wingdings-3.html
+    let bullet: &'static [char] = match (font.as_str(), code) {
+        ("symbol", 0xb7) => &['\u{2022}'],
+        ("symbol", 0xa8) => &['\u{2666}'],
+        ("symbol", 0xde) => &['\u{21d2}'],
+        ("wingdings", 0x6c) => &['\u{25cf}'],
+        ("wingdings", 0x6e) => &['\u{25a0}'],
+        ("wingdings", 0x6f) => &['\u{25a1}'],
+        ("wingdings", 0x71) => &['\u{2751}'],
+        ("wingdings", 0x76) => &['\u{2756}'],
+        ("wingdings", 0x77) => &['\u{2b25}'],
+        ("wingdings", 0x9f) => &['\u{2022}'],
+        ("wingdings", 0xa7) => &['\u{25aa}'],
+        ("wingdings", 0xa8) => &['\u{25fb}'],
+        ("wingdings", 0xd8) => &['\u{27a2}'],
+        ("wingdings", 0xe0) => &['\u{2794}'],
+        ("wingdings", 0xfc) => &['\u{2714}'],
+        ("wingdings", 0xfe) => &['\u{2611}'],
+        ("wingdings 2", 0xae) => &['\u{25c6}'],
+        ("wingdings 3", 0x7d) => &['\u{25b6}'],
+
+        // Browsers usually don't have these fonts, so render unknown symbols
+        // as a plain bullet instead of the character they're mapped to
+        ("symbol" | "wingdings" | "wingdings 2" | "wingdings 3", code) => {
+            warn!(
+                "Unsupported bullet {:#x} in font {}; falling back to a plain bullet",
+                code, font
+            );
+
+            &['\u{2022}']
+        }
+
+        _ => return None,
+    };
+
+    Some(bullet)
+}
+
+fn escape_css_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -5,6 +5,7 @@ mod render;
 mod text;
 
 use crate::page::Renderer;
+use crate::utils::escape_html;
 use color_eyre::Result;
 use onenote_parser::contents::MathInlineObject;
 use parser::Parser;
@@ -12,11 +13,28 @@ use render::render_equation;
 
 impl<'a> Renderer<'a> {
     pub(crate) fn render_math(&self, segments: Vec<(String, MathInlineObject)>) -> Result<String> {
+        let alt_text = if self.section.options.accessible {
+            Some(alt_text(&segments))
+        } else {
+            None
+        };
+
         let mut parser = Parser::new(segments)?;
         let equation = parser.parse()?;
 
-        let markup = render_equation(equation)?;
+        let markup = render_equation(equation, alt_text.as_deref())?;
 
         Ok(markup)
     }
 }
+
+/// Build a plain-text description of an equation from its linear format source.
+fn alt_text(segments: &[(String, MathInlineObject)]) -> String {
+    let text: String = segments
+        .iter()
+        .flat_map(|(text, _)| text.chars())
+        .filter(|c| !c.is_control())
+        .collect();
+
+    escape_html(text.trim())
+}
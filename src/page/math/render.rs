@@ -6,9 +6,14 @@ use finl_unicode::categories::CharacterCategories;
 use itertools::Itertools;
 use log::warn;
 
-pub(super) fn render_equation(equation: Equation) -> Result<String> {
+pub(super) fn render_equation(equation: Equation, alt_text: Option<&str>) -> Result<String> {
+    let alt_text = alt_text
+        .map(|text| format!(" alttext=\"{}\"", text))
+        .unwrap_or_default();
+
     Ok(format!(
-        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>",
+        "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"{}>{}</math>",
+        alt_text,
         render_eq(equation)?
     ))
 }
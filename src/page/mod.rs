@@ -1,26 +1,44 @@
+use crate::page::embedded_file::page_embedded_files;
+use crate::page::printout::is_printout_page;
 use crate::section;
 use crate::utils::StyleSet;
 use color_eyre::Result;
+use itertools::Itertools;
 use onenote_parser::page::{Page, PageContent};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 
+pub(crate) mod assets;
+pub(crate) mod code;
+pub(crate) mod color;
 pub(crate) mod content;
 pub(crate) mod embedded_file;
+pub(crate) mod fonts;
 pub(crate) mod image;
 pub(crate) mod ink;
 pub(crate) mod list;
 pub(crate) mod math;
 pub(crate) mod note_tag;
 pub(crate) mod outline;
+pub(crate) mod printout;
 pub(crate) mod rich_text;
 pub(crate) mod table;
+pub(crate) mod table_export;
+pub(crate) mod text;
 
 pub(crate) struct Renderer<'a> {
     output: PathBuf,
     section: &'a mut section::Renderer,
 
+    title: String,
     in_list: bool,
+    in_table: bool,
+
+    /// The embedded files written for the page.
+    embedded_files: Vec<embedded_file::WrittenFile>,
+    tables: Vec<Vec<Vec<String>>>,
+    tables_workbook: bool,
+    fonts: BTreeSet<String>,
     global_styles: HashMap<String, StyleSet>,
     global_classes: HashSet<String>,
 }
@@ -30,7 +48,13 @@ impl<'a> Renderer<'a> {
         Self {
             output,
             section,
+            title: String::new(),
             in_list: false,
+            in_table: false,
+            embedded_files: Vec::new(),
+            tables: Vec::new(),
+            tables_workbook: false,
+            fonts: BTreeSet::new(),
             global_styles: HashMap::new(),
             global_classes: HashSet::new(),
         }
@@ -38,6 +62,7 @@ impl<'a> Renderer<'a> {
 
     pub(crate) fn render_page(&mut self, page: &Page) -> Result<String> {
         let title_text = page.title_text().unwrap_or("Untitled Page");
+        self.title = title_text.trim().replace('/', "_");
 
         let mut content = String::new();
 
@@ -64,15 +89,60 @@ impl<'a> Renderer<'a> {
             content.push_str(&title_field);
         }
 
-        let page_content = page
+        // Printouts link to the embedded file they were created from, so
+        // write the embedded files before rendering the page.
+        let files = page_embedded_files(page);
+        for file in &files {
+            self.write_embedded_file(file)?;
+        }
+
+        let groups = page
             .contents()
             .iter()
-            .map(|content| self.render_page_content(content))
-            .collect::<Result<String>>()?;
+            .enumerate()
+            .chunk_by(|(_, content)| content.image().is_some_and(is_printout_page));
+
+        for (is_printout, group) in &groups {
+            if is_printout {
+                let group = group.collect_vec();
+                let pages = group
+                    .iter()
+                    .filter_map(|(_, content)| content.image())
+                    .collect_vec();
+
+                // Embedded files right before or after the printout
+                let start = group.first().map(|(i, _)| *i).unwrap_or_default();
+                let adjacent = [start.checked_sub(1), Some(start + group.len())]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| page.contents().get(i)?.embedded_file())
+                    .collect_vec();
+
+                content.push_str(&self.render_printout(&pages, &adjacent)?);
+                continue;
+            }
+
+            for (_, page_content) in group {
+                content.push_str(&self.render_page_content(page_content)?);
+            }
+        }
+
+        let content = self.write_tables_workbook(content)?;
+
+        let default_font = self.font_family("Calibri");
+        let font_faces = self.render_font_faces()?;
 
-        content.push_str(&page_content);
+        self.section.fonts.extend(self.fonts.iter().cloned());
 
-        crate::templates::page::render(title_text, &content, &self.global_styles)
+        crate::templates::page::render(
+            title_text,
+            &content,
+            &self.global_styles,
+            &default_font,
+            &font_faces,
+            self.section.color,
+            &self.section.options,
+        )
     }
 
     pub(crate) fn gen_class(&mut self, prefix: &str) -> String {
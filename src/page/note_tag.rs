@@ -1,5 +1,6 @@
 use crate::page::Renderer;
-use crate::utils::StyleSet;
+use crate::page::color::ColorRole;
+use crate::utils::{AttributeSet, StyleSet, escape_html};
 use log::warn;
 use onenote_parser::contents::{NoteTag, OutlineElement};
 use onenote_parser::property::common::ColorRef;
@@ -15,7 +16,7 @@ const COLOR_YELLOW: &str = "#ffd678";
 
 const ICON_ARROW_RIGHT: &str = include_str!("../../assets/icons/arrow-right-line.svg");
 const ICON_AWARD: &str = include_str!("../../assets/icons/award-line.svg");
-const ICON_BOOK: &str = include_str!("../../assets/icons/book-open-line.svg");
+pub(crate) const ICON_BOOK: &str = include_str!("../../assets/icons/book-open-line.svg");
 const ICON_BUBBLE: &str = include_str!("../../assets/icons/chat-4-line.svg");
 const ICON_CHECKBOX_COMPLETE: &str = include_str!("../../assets/icons/checkbox-fill.svg");
 const ICON_CHECKBOX_EMPTY: &str = include_str!("../../assets/icons/checkbox-blank-line.svg");
@@ -24,14 +25,14 @@ const ICON_CIRCLE: &str = include_str!("../../assets/icons/checkbox-blank-circle
 const ICON_CONTACT: &str = include_str!("../../assets/icons/contacts-line.svg");
 const ICON_EMAIL: &str = include_str!("../../assets/icons/send-plane-2-line.svg");
 const ICON_ERROR: &str = include_str!("../../assets/icons/error-warning-line.svg");
-const ICON_FILM: &str = include_str!("../../assets/icons/film-line.svg");
+pub(crate) const ICON_FILM: &str = include_str!("../../assets/icons/film-line.svg");
 const ICON_FLAG: &str = include_str!("../../assets/icons/flag-fill.svg");
 const ICON_HOME: &str = include_str!("../../assets/icons/home-4-line.svg");
 const ICON_LIGHT_BULB: &str = include_str!("../../assets/icons/lightbulb-line.svg");
 const ICON_LINK: &str = include_str!("../../assets/icons/link.svg");
 const ICON_LOCK: &str = include_str!("../../assets/icons/lock-line.svg");
-const ICON_MUSIC: &str = include_str!("../../assets/icons/music-fill.svg");
-const ICON_PAPER: &str = include_str!("../../assets/icons/file-list-2-line.svg");
+pub(crate) const ICON_MUSIC: &str = include_str!("../../assets/icons/music-fill.svg");
+pub(crate) const ICON_PAPER: &str = include_str!("../../assets/icons/file-list-2-line.svg");
 const ICON_PEN: &str = include_str!("../../assets/icons/mark-pen-line.svg");
 const ICON_PERSON: &str = include_str!("../../assets/icons/user-line.svg");
 const ICON_PHONE: &str = include_str!("../../assets/icons/phone-line.svg");
@@ -74,11 +75,14 @@ impl<'a> Renderer<'a> {
         for note_tag in note_tags {
             if let Some(def) = note_tag.definition() {
                 if let Some(ColorRef::Manual { r, g, b }) = def.highlight_color() {
-                    styles.set("background-color", format!("rgb({},{},{})", r, g, b));
+                    styles.set(
+                        "background-color",
+                        self.css_color((r, g, b), ColorRole::Background),
+                    );
                 }
 
                 if let Some(ColorRef::Manual { r, g, b }) = def.text_color() {
-                    styles.set("color", format!("rgb({},{},{})", r, g, b));
+                    styles.set("color", self.css_color((r, g, b), ColorRole::Foreground));
                 }
 
                 if def.shape() != NoteTagShape::NoIcon {
@@ -94,11 +98,15 @@ impl<'a> Renderer<'a> {
                             .insert(format!(".{} > svg", class), icon_style);
                     }
 
-                    markup.push_str(&format!(
-                        "<span class=\"{}\">{}</span>",
-                        icon_classes.join(" "),
-                        icon
-                    ));
+                    let mut attrs = AttributeSet::new();
+                    attrs.set("class", icon_classes.join(" "));
+
+                    if self.section.options.accessible {
+                        attrs.set("role", "img".to_string());
+                        attrs.set("aria-label", escape_html(def.label()));
+                    }
+
+                    markup.push_str(&format!("<span {}>{}</span>", attrs, icon));
                 }
             }
         }
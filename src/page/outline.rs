@@ -1,6 +1,8 @@
 use crate::page::Renderer;
+use crate::page::printout::is_printout_page;
 use crate::utils::{AttributeSet, StyleSet, px};
 use color_eyre::Result;
+use itertools::Itertools;
 use onenote_parser::contents::{Outline, OutlineElement, OutlineItem};
 
 impl<'a> Renderer<'a> {
@@ -89,13 +91,28 @@ impl<'a> Renderer<'a> {
 
         self.in_list = is_list;
 
-        contents.extend(
-            element
-                .contents()
-                .iter()
-                .map(|content| self.render_content(content))
-                .collect::<Result<Vec<_>, _>>()?,
-        );
+        let groups = element
+            .contents()
+            .iter()
+            .chunk_by(|content| content.image().is_some_and(is_printout_page));
+
+        for (is_printout, group) in &groups {
+            if is_printout {
+                let pages = group.filter_map(|content| content.image()).collect_vec();
+                let adjacent = element
+                    .contents()
+                    .iter()
+                    .filter_map(|content| content.embedded_file())
+                    .collect_vec();
+
+                contents.push_str(&self.render_printout(&pages, &adjacent)?);
+                continue;
+            }
+
+            for content in group {
+                contents.push_str(&self.render_content(content)?);
+            }
+        }
 
         self.in_list = false;
 
@@ -0,0 +1,167 @@
+use crate::page::Renderer;
+use crate::utils::{AttributeSet, StyleSet, px};
+use color_eyre::Result;
+use itertools::Itertools;
+use onenote_parser::contents::{EmbeddedFile, Image, OutlineElement};
+use std::path::Path;
+
+impl<'a> Renderer<'a> {
+    /// Render the page images of a file printout as a paginated viewer.
+    ///
+    /// The `adjacent` embedded files are the files right before or after the
+    /// printout, one of which may be the file the printout was created from.
+    pub(crate) fn render_printout(
+        &mut self,
+        pages: &[&Image],
+        adjacent: &[&EmbeddedFile],
+    ) -> Result<String> {
+        let file = self.printout_file(pages, adjacent);
+        let mut content = String::new();
+        let mut styles = StyleSet::new();
+
+        let first = match pages.first() {
+            Some(first) => first,
+            None => return Ok(content),
+        };
+
+        if first.offset_horizontal().is_some() || first.offset_vertical().is_some() {
+            styles.set("position", "absolute".to_string());
+        }
+
+        if let Some(offset) = first.offset_horizontal() {
+            styles.set("left", px(offset));
+        }
+
+        if let Some(offset) = first.offset_vertical() {
+            styles.set("top", px(offset));
+        }
+
+        if let Some(width) = first.layout_max_width() {
+            styles.set("max-width", px(width));
+        }
+
+        let mut attrs = AttributeSet::new();
+        attrs.set("class", "printout".to_string());
+
+        if styles.len() > 0 {
+            attrs.set("style", styles.to_string());
+        }
+
+        content.push_str(&format!("<div {}>", attrs));
+
+        content.push_str("<div class=\"printout-toolbar\">");
+        content.push_str("<button class=\"printout-prev\" type=\"button\">&lsaquo;</button>");
+        content.push_str(&format!(
+            "<span class=\"printout-position\">Page <span class=\"printout-current\">1</span> of {}</span>",
+            pages.len()
+        ));
+        content.push_str("<button class=\"printout-next\" type=\"button\">&rsaquo;</button>");
+
+        if let Some(file) = &file {
+            content.push_str(&format!(
+                "<a class=\"printout-download\" href=\"{}\" download>Download original</a>",
+                file
+            ));
+        }
+
+        content.push_str("</div>");
+
+        for (i, page) in pages.iter().enumerate() {
+            let data = match page.data() {
+                Some(data) => data,
+                None => continue,
+            };
+
+            let (filename, thumbnail) = self.write_image(page, data)?;
+            let number = page.displayed_page_number().unwrap_or(i as u32 + 1);
+
+            let mut attrs = AttributeSet::new();
+            attrs.set("src", thumbnail.unwrap_or_else(|| filename.clone()));
+            attrs.set("alt", format!("Page {}", number));
+
+            let class = if i == 0 {
+                "printout-page active"
+            } else {
+                "printout-page"
+            };
+
+            content.push_str(&format!(
+                "<figure class=\"{}\"><a href=\"{}\"><img {} /></a><figcaption>{}</figcaption></figure>",
+                class, filename, attrs, number
+            ));
+        }
+
+        content.push_str("</div>");
+
+        let note_tags = pages
+            .iter()
+            .flat_map(|page| page.note_tags())
+            .unique()
+            .cloned()
+            .collect_vec();
+
+        Ok(self.render_with_note_tags(&note_tags, content))
+    }
+}
+
+impl<'a> Renderer<'a> {
+    /// Returns the URL of the embedded file a printout was created from.
+    ///
+    /// The printout is matched to the embedded file of the page with the same
+    /// name or, failing that, to one of the `adjacent` embedded files. Returns
+    /// `None` if there's no unique match.
+    fn printout_file(&self, pages: &[&Image], adjacent: &[&EmbeddedFile]) -> Option<String> {
+        let names = pages
+            .iter()
+            .filter_map(|page| page.image_filename())
+            .map(file_stem)
+            .unique()
+            .collect_vec();
+
+        let by_name = self
+            .embedded_files
+            .iter()
+            .filter(|file| names.contains(&file_stem(&file.name)))
+            .map(|file| file.url.as_str())
+            .collect_vec();
+
+        let candidates = if by_name.is_empty() {
+            adjacent
+                .iter()
+                .filter_map(|file| self.embedded_file_url(file))
+                .collect_vec()
+        } else {
+            by_name
+        };
+
+        candidates
+            .into_iter()
+            .unique()
+            .exactly_one()
+            .ok()
+            .map(str::to_string)
+    }
+
+    /// Returns whether the outline element only contains printout pages.
+    pub(crate) fn is_printout_element(&self, element: &OutlineElement) -> bool {
+        !element.contents().is_empty()
+            && element.children().is_empty()
+            && element
+                .contents()
+                .iter()
+                .all(|content| content.image().is_some_and(is_printout_page))
+    }
+}
+
+fn file_stem(name: &str) -> String {
+    Path::new(name)
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase()
+}
+
+/// Returns whether the image is a page of a file printout.
+pub(crate) fn is_printout_page(image: &Image) -> bool {
+    image.displayed_page_number().is_some()
+}
@@ -1,4 +1,5 @@
 use crate::page::Renderer;
+use crate::page::color::ColorRole;
 use crate::utils::{AttributeSet, StyleSet, px};
 use color_eyre::Result;
 use color_eyre::eyre::ContextCompat;
@@ -33,6 +34,19 @@ impl<'a> Renderer<'a> {
             attrs.set("style", style.to_string());
         }
 
+        let list_heading_level = text
+            .paragraph_style()
+            .style_id()
+            .and_then(heading_level)
+            .filter(|_| self.in_list && self.section.options.accessible);
+
+        if let Some(level) = list_heading_level {
+            attrs.set("role", "heading".to_string());
+            attrs.set("aria-level", level.to_string());
+
+            return Ok(format!("<span {}>{}</span>", attrs, content));
+        }
+
         match text.paragraph_style().style_id() {
             Some(t) if !self.in_list && is_tag(t) => {
                 Ok(format!("<{} {}>{}</{}>", t, attrs, content, t))
@@ -206,7 +220,7 @@ impl<'a> Renderer<'a> {
     }
 
     fn render_hyperlink(
-        &self,
+        &mut self,
         text: String,
         style: &ParagraphStyling,
         in_hyperlink: bool,
@@ -233,7 +247,7 @@ impl<'a> Renderer<'a> {
         }
     }
 
-    fn parse_paragraph_styles(&self, text: &RichText) -> StyleSet {
+    fn parse_paragraph_styles(&mut self, text: &RichText) -> StyleSet {
         if !text.embedded_objects().is_empty() {
             assert_eq!(
                 text.text(),
@@ -276,7 +290,7 @@ impl<'a> Renderer<'a> {
         styles
     }
 
-    fn parse_style(&self, style: &ParagraphStyling) -> StyleSet {
+    fn parse_style(&mut self, style: &ParagraphStyling) -> StyleSet {
         let mut styles = StyleSet::new();
 
         if style.math_formatting() {
@@ -308,7 +322,7 @@ impl<'a> Renderer<'a> {
         }
 
         if let Some(font) = style.font() {
-            styles.set("font-family", font.to_string());
+            styles.set("font-family", self.font_family(font));
         }
 
         if let Some(size) = style.font_size() {
@@ -316,11 +330,14 @@ impl<'a> Renderer<'a> {
         }
 
         if let Some(ColorRef::Manual { r, g, b }) = style.font_color() {
-            styles.set("color", format!("rgb({},{},{})", r, g, b));
+            styles.set("color", self.css_color((r, g, b), ColorRole::Foreground));
         }
 
         if let Some(ColorRef::Manual { r, g, b }) = style.highlight() {
-            styles.set("background-color", format!("rgb({},{},{})", r, g, b));
+            styles.set(
+                "background-color",
+                self.css_color((r, g, b), ColorRole::Background),
+            );
         }
 
         if style.paragraph_alignment().is_some() {
@@ -376,6 +393,18 @@ fn is_tag(tag: &str) -> bool {
     !matches!(tag, "PageDateTime" | "PageTitle")
 }
 
+fn heading_level(tag: &str) -> Option<u8> {
+    match tag {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
 fn fix_newlines(text: String) -> String {
     static REGEX_LEADING_SPACES: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"<br>(\s+)").expect("failed to compile regex"));
@@ -1,7 +1,9 @@
 use crate::page::Renderer;
+use crate::page::color::ColorRole;
 use crate::utils::{AttributeSet, StyleSet, px};
 use color_eyre::Result;
-use onenote_parser::contents::{OutlineElement, Table, TableCell};
+use log::warn;
+use onenote_parser::contents::{OutlineElement, Table, TableCell, TableRow};
 
 impl<'a> Renderer<'a> {
     pub(crate) fn render_table(&mut self, table: &Table) -> Result<String> {
@@ -10,7 +12,7 @@ impl<'a> Renderer<'a> {
         styles.set("border-collapse", "collapse".to_string());
 
         if table.borders_visible() {
-            styles.set("border", "1pt solid #A3A3A3".to_string());
+            styles.set("border", "1pt solid var(--outline)".to_string());
         }
 
         let mut attributes = AttributeSet::new();
@@ -22,40 +24,73 @@ impl<'a> Renderer<'a> {
             attributes.set("border", "1".to_string());
         }
 
+        let cols = table.cols() as usize;
+        let locked_cols = calc_locked_cols(table.cols_locked(), cols);
+
+        let mut col_widths = table.col_widths().to_vec();
+        col_widths.resize(cols, 0.0);
+
+        // Tables nested in table cells are usually used for layout, so only
+        // top-level tables get a header row
+        let nested = self.in_table;
+
+        let options = &self.section.options;
+        let header_row = table.contents().len() > 1
+            && ((options.table_headers && !nested)
+                || (options.accessible && table.contents().first().is_some_and(is_header_row)));
+
+        if header_row && options.sortable_tables {
+            attributes.set("class", "sortable".to_string());
+        }
+
         content.push_str(&format!("<table {}>", attributes));
 
-        let locked_cols = calc_locked_cols(table.cols_locked(), table.cols());
+        self.in_table = true;
 
-        let mut col_widths = table.col_widths().to_vec();
-        col_widths.extend(vec![0.0; table.cols() as usize - col_widths.len()]);
-        let col_widths = &*col_widths;
+        for (i, row) in table.contents().iter().enumerate() {
+            let header = header_row && i == 0;
+
+            match (header_row, i) {
+                (true, 0) => content.push_str("<thead>"),
+                (true, 1) => content.push_str("</thead><tbody>"),
+                _ => {}
+            }
 
-        for row in table.contents() {
             content.push_str("<tr>");
 
-            assert_eq!(row.contents().len(), col_widths.len());
-
-            let cells = row
-                .contents()
-                .iter()
-                .zip(col_widths.iter().copied())
-                .zip(locked_cols.iter().copied())
-                .map(|((cell, width), locked)| {
-                    if locked {
-                        (cell, Some(width))
-                    } else {
-                        (cell, None)
-                    }
-                });
-
-            for (cell, width) in cells {
-                self.render_table_cell(&mut content, cell, width)?;
+            if row.contents().len() != cols {
+                warn!(
+                    "Table row {} has {} cells but the table has {} columns",
+                    i,
+                    row.contents().len(),
+                    cols
+                );
+            }
+
+            for (j, cell) in row.contents().iter().enumerate() {
+                let width = col_widths
+                    .get(j)
+                    .copied()
+                    .filter(|_| locked_cols.get(j).copied().unwrap_or_default());
+
+                self.render_table_cell(&mut content, cell, width, header)?;
+            }
+
+            for _ in row.contents().len()..cols {
+                content.push_str(if header { "<th></th>" } else { "<td></td>" });
             }
 
             content.push_str("</tr>");
         }
 
+        self.in_table = nested;
+
+        if header_row {
+            content.push_str("</tbody>");
+        }
+
         content.push_str("</table>");
+        content.push_str(&self.export_table(table)?);
 
         Ok(self.render_with_note_tags(table.note_tags(), content))
     }
@@ -65,10 +100,10 @@ impl<'a> Renderer<'a> {
         contents: &mut String,
         cell: &TableCell,
         width: Option<f32>,
+        header: bool,
     ) -> Result<()> {
         let mut styles = StyleSet::new();
         styles.set("padding", "2pt".to_string());
-        styles.set("vertical-align", "top".to_string());
         styles.set("min-width", px(1.0));
 
         if let Some(width) = width {
@@ -78,21 +113,27 @@ impl<'a> Renderer<'a> {
         if let Some(color) = cell.background_color() {
             styles.set(
                 "background",
-                format!("rgb({}, {}, {})", color.r(), color.g(), color.b()),
+                self.css_color((color.r(), color.g(), color.b()), ColorRole::Background),
             )
         }
 
         let mut attrs = AttributeSet::new();
         attrs.set("style", styles.to_string());
 
-        contents.push_str(&format!("<td {}>", attrs));
+        let tag = if header { "th" } else { "td" };
+
+        if header {
+            attrs.set("scope", "col".to_string());
+        }
+
+        contents.push_str(&format!("<{} {}>", tag, attrs));
 
         let cell_level = self.table_cell_level(cell.contents());
 
         let elements = cell.contents().iter().map(|el| (el, 0, cell_level));
         contents.push_str(&self.render_list(elements, cell.outline_indent_distance().value())?);
 
-        contents.push_str("</td>");
+        contents.push_str(&format!("</{}>", tag));
 
         Ok(())
     }
@@ -106,12 +147,30 @@ impl<'a> Renderer<'a> {
     }
 }
 
-fn calc_locked_cols(data: &[u8], count: u32) -> Vec<bool> {
-    if data.is_empty() {
-        return vec![false; count as usize];
-    }
+/// Treat a row as a header row if all of its text is bold.
+fn is_header_row(row: &TableRow) -> bool {
+    let mut texts = row
+        .contents()
+        .iter()
+        .flat_map(|cell| cell.contents())
+        .flat_map(|element| element.contents())
+        .filter_map(|content| content.rich_text())
+        .filter(|text| !text.text().trim().is_empty())
+        .peekable();
+
+    texts.peek().is_some()
+        && texts.all(|text| {
+            text.paragraph_style().bold()
+                || (!text.text_run_formatting().is_empty()
+                    && text.text_run_formatting().iter().all(|style| style.bold()))
+        })
+}
 
+fn calc_locked_cols(data: &[u8], count: usize) -> Vec<bool> {
     (0..count)
-        .map(|i| data[i as usize / 8] & (1 << (i % 8)) == 1)
+        .map(|i| {
+            data.get(i / 8)
+                .is_some_and(|byte| byte & (1 << (i % 8)) != 0)
+        })
         .collect()
 }
@@ -0,0 +1,117 @@
+use crate::page::Renderer;
+use crate::page::text::table_text;
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+use itertools::Itertools;
+use log::warn;
+use onenote_parser::contents::Table;
+use rust_xlsxwriter::Workbook;
+
+/// Stands in for the URL of the tables workbook until it has been written.
+const TABLES_WORKBOOK_URL: &str = "\u{fdd0}tables-workbook\u{fdd0}";
+
+/// The maximum number of characters Excel stores in a cell.
+const MAX_CELL_LENGTH: usize = 32_767;
+
+impl<'a> Renderer<'a> {
+    /// Export the table contents and return the markup of the download links.
+    pub(crate) fn export_table(&mut self, table: &Table) -> Result<String> {
+        let options = &self.section.options;
+        let (csv, xlsx) = (options.export_tables_csv, options.export_tables_xlsx);
+
+        if !csv && !xlsx {
+            return Ok(String::new());
+        }
+
+        let rows = table_text(table);
+        self.tables.push(rows);
+
+        let index = self.tables.len();
+        let mut links = Vec::new();
+
+        if csv {
+            let data = to_csv(&self.tables[index - 1]);
+            let name = format!("{} - Table {}.csv", self.title, index);
+            let url = self.write_file(data.as_bytes(), Some("csv"), |this| {
+                this.determine_filename(&name)
+            })?;
+
+            links.push(format!("<a href=\"{}\" download>Download as CSV</a>", url));
+        }
+
+        if xlsx {
+            // The workbook is written once all tables of the page are known
+            self.tables_workbook = true;
+
+            links.push(format!(
+                "<a href=\"{}\" download>Download as XLSX</a>",
+                TABLES_WORKBOOK_URL
+            ));
+        }
+
+        Ok(format!(
+            "<div class=\"table-export\">{}</div>",
+            links.join(" &middot; ")
+        ))
+    }
+
+    /// Write all tables of the page into a workbook with one sheet per table
+    /// and insert its URL into the download links of the page `content`.
+    pub(crate) fn write_tables_workbook(&mut self, content: String) -> Result<String> {
+        if !self.tables_workbook {
+            return Ok(content);
+        }
+
+        let mut workbook = Workbook::new();
+
+        for (i, rows) in self.tables.iter().enumerate() {
+            let sheet = workbook.add_worksheet();
+            sheet.set_name(format!("Table {}", i + 1))?;
+
+            for (row, cells) in rows.iter().enumerate() {
+                for (col, text) in cells.iter().enumerate() {
+                    let text = match text.char_indices().nth(MAX_CELL_LENGTH) {
+                        Some((end, _)) => {
+                            warn!(
+                                "Truncating cell {}:{} of table {} on page {} to {} characters",
+                                row + 1,
+                                col + 1,
+                                i + 1,
+                                self.title,
+                                MAX_CELL_LENGTH
+                            );
+
+                            &text[..end]
+                        }
+                        None => text.as_str(),
+                    };
+
+                    sheet.write_string(row as u32, col as u16, text)?;
+                }
+            }
+        }
+
+        let data = workbook.save_to_buffer()?;
+        let name = format!("{} - Tables.xlsx", self.title);
+        let url = self
+            .write_file(&data, Some("xlsx"), |this| this.determine_filename(&name))
+            .wrap_err("Failed to write table workbook")?;
+
+        Ok(content.replace(TABLES_WORKBOOK_URL, &url))
+    }
+}
+
+fn to_csv(rows: &[Vec<String>]) -> String {
+    rows.iter()
+        .map(|cells| cells.iter().map(|text| escape_csv(text)).join(","))
+        .map(|line| line + "\r\n")
+        .collect()
+}
+
+fn escape_csv(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
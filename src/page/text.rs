@@ -0,0 +1,74 @@
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use onenote_parser::contents::{Content, OutlineElement, OutlineItem, RichText, Table};
+use regex::Regex;
+use std::slice;
+
+/// Extract the plain text of a rich text paragraph.
+pub(crate) fn rich_text_plain(text: &RichText) -> String {
+    static REGEX_HYPERLINK: Lazy<Regex> =
+        Lazy::new(|| Regex::new("\u{fddf}HYPERLINK \"[^\"]*\"").expect("failed to compile regex"));
+
+    REGEX_HYPERLINK
+        .replace_all(text.text(), "")
+        .replace(['\u{000b}', '\r'], "\n")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+/// Extract the plain text of outline elements, one paragraph per line.
+pub(crate) fn outline_elements_text(elements: &[OutlineElement]) -> String {
+    elements
+        .iter()
+        .flat_map(|element| {
+            let text = element
+                .contents()
+                .iter()
+                .filter_map(|content| match content {
+                    Content::RichText(text) => Some(rich_text_plain(text)),
+                    Content::Table(table) => Some(nested_table_text(table)),
+                    _ => None,
+                });
+
+            let children = Some(element.children())
+                .filter(|children| !children.is_empty())
+                .map(outline_items_text);
+
+            text.chain(children).collect_vec()
+        })
+        .join("\n")
+}
+
+/// Extract the plain text of outline items, one paragraph per line.
+pub(crate) fn outline_items_text(items: &[OutlineItem]) -> String {
+    items
+        .iter()
+        .map(|item| match item {
+            OutlineItem::Element(element) => outline_elements_text(slice::from_ref(element)),
+            OutlineItem::Group(group) => outline_items_text(group.outlines()),
+        })
+        .join("\n")
+}
+
+/// Extract the plain text of a table nested in a table cell, one row per line
+/// with tab-separated cells.
+fn nested_table_text(table: &Table) -> String {
+    table_text(table)
+        .iter()
+        .map(|cells| cells.join("\t"))
+        .join("\n")
+}
+
+/// Extract the plain text of every table cell.
+pub(crate) fn table_text(table: &Table) -> Vec<Vec<String>> {
+    table
+        .contents()
+        .iter()
+        .map(|row| {
+            row.contents()
+                .iter()
+                .map(|cell| outline_elements_text(cell.contents()))
+                .collect()
+        })
+        .collect()
+}
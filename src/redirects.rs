@@ -0,0 +1,145 @@
+use crate::options::Options;
+use crate::output::Sink;
+use crate::templates;
+use color_eyre::eyre::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+
+/// The file in each section directory that records the page file names.
+const MANIFEST: &str = ".one2html-pages.json";
+
+/// The page file names of a section from previous conversions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    /// The file names of the pages by page key.
+    pages: BTreeMap<String, String>,
+
+    /// Previous file names and the keys of the pages they redirect to.
+    redirects: BTreeMap<String, String>,
+}
+
+/// A rendered page of a section.
+pub(crate) struct PageFile<'a> {
+    /// Identifies the page between conversions: the page title, followed by
+    /// `#2`, `#3`, ... for further pages with the same title.
+    pub(crate) key: String,
+
+    pub(crate) title: &'a str,
+    pub(crate) file_name: String,
+}
+
+/// Write redirect pages for the pages whose file name changed since the
+/// previous conversion and record the current file names.
+///
+/// The `keys` are the keys of all pages of the section, including pages
+/// excluded by the filters. Files of pages that no longer exist are removed.
+pub(crate) fn update(
+    section_dir: &Path,
+    pages: &[PageFile],
+    keys: &[String],
+    options: &Options,
+    sink: &Sink,
+) -> Result<()> {
+    let manifest_file = section_dir.join(MANIFEST);
+
+    let mut manifest = match sink.read_to_string(&manifest_file) {
+        Some(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!(
+                "Ignoring invalid page manifest {}: {}",
+                manifest_file.display(),
+                e
+            );
+
+            Manifest::default()
+        }),
+        None => Manifest::default(),
+    };
+
+    // The manifest may have been edited, so only accept files in the section
+    // directory
+    let valid = |file_name: &String| {
+        is_file_name(file_name) || {
+            warn!(
+                "Ignoring invalid file name {:?} in page manifest {}",
+                file_name,
+                manifest_file.display()
+            );
+
+            false
+        }
+    };
+    manifest.pages.retain(|_, file_name| valid(file_name));
+    manifest.redirects.retain(|file_name, _| valid(file_name));
+
+    for page in pages {
+        if let Some(previous) = manifest.pages.get(&page.key)
+            && *previous != page.file_name
+        {
+            manifest
+                .redirects
+                .insert(previous.clone(), page.key.clone());
+        }
+
+        manifest
+            .pages
+            .insert(page.key.clone(), page.file_name.clone());
+    }
+
+    let current = pages
+        .iter()
+        .map(|page| page.file_name.clone())
+        .collect::<HashSet<_>>();
+
+    // Pages that have been renamed or deleted can't be told apart, so their
+    // files and redirects are removed instead of leaving stale pages behind
+    let stale = manifest
+        .pages
+        .iter()
+        .filter(|(key, _)| !keys.contains(key))
+        .map(|(_, file_name)| file_name.clone())
+        .chain(
+            manifest
+                .redirects
+                .iter()
+                .filter(|(_, key)| !keys.contains(key))
+                .map(|(file_name, _)| file_name.clone()),
+        )
+        .collect::<Vec<_>>();
+
+    for file_name in stale.iter().filter(|name| !current.contains(*name)) {
+        sink.remove(&section_dir.join(file_name))?;
+    }
+
+    manifest.pages.retain(|key, _| keys.contains(key));
+    manifest.redirects.retain(|_, key| keys.contains(key));
+
+    // Files that belong to a page again don't redirect anymore
+    manifest
+        .redirects
+        .retain(|file_name, _| !current.contains(file_name));
+
+    for (file_name, key) in &manifest.redirects {
+        let Some(page) = pages.iter().find(|page| page.key == *key) else {
+            continue;
+        };
+
+        let html = templates::redirect::render(page.title, &page.file_name, options)?;
+        sink.write(&section_dir.join(file_name), html)?;
+    }
+
+    sink.write(&manifest_file, serde_json::to_string_pretty(&manifest)?)?;
+
+    Ok(())
+}
+
+/// Returns whether the name is a plain file name without any directories.
+fn is_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(file_name)), None) if file_name == name
+    )
+}
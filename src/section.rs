@@ -1,86 +1,212 @@
-use crate::utils::sanitize_output_filename;
-use crate::{page, templates};
+use crate::notebook::{RgbColor, prepare_color};
+use crate::options::{Options, PageNames};
+use crate::output::Sink;
+use crate::utils::{sanitize_output_filename, slug};
+use crate::{page, redirects, templates};
 use color_eyre::eyre::Result;
 use onenote_parser::section::Section;
-use std::collections::HashSet;
-use std::fs;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 pub(crate) struct Renderer {
     pub(crate) files: HashSet<String>,
-    pub(crate) pages: HashSet<String>,
+    pub(crate) options: Options,
+
+    /// Where the rendered files are written to.
+    pub(crate) sink: Sink,
+
+    /// The accent color of the section.
+    pub(crate) color: Option<RgbColor>,
+
+    /// The shared directory content-addressed assets are written to.
+    pub(crate) assets_dir: Option<PathBuf>,
+
+    /// The shared directory substitute font files are bundled to.
+    pub(crate) fonts_dir: Option<PathBuf>,
+
+    /// The fonts used in the rendered pages.
+    pub(crate) fonts: BTreeSet<String>,
 }
 
 impl Renderer {
-    pub fn new() -> Self {
+    pub fn new(options: Options, sink: Sink) -> Self {
         Renderer {
             files: Default::default(),
-            pages: Default::default(),
+            options,
+            sink,
+            color: None,
+            assets_dir: None,
+            fonts_dir: None,
+            fonts: BTreeSet::new(),
         }
     }
 
-    pub fn render(&mut self, section: &Section, output_dir: &Path) -> Result<PathBuf> {
-        let section_dir = output_dir.join(sanitize_filename::sanitize(section.display_name()));
-
-        if !section_dir.is_dir() {
-            fs::create_dir(&section_dir)?;
-        }
-
-        let mut toc = Vec::new();
+    /// Render the pages of a section matching the page filters and return
+    /// the section's directory.
+    ///
+    /// Returns `None` if the filters exclude all pages of the section.
+    pub fn render(&mut self, section: &Section, output_dir: &Path) -> Result<Option<PathBuf>> {
         let mut fallback_title_index = 0;
 
-        for page_series in section.page_series() {
-            for page in page_series.pages() {
+        let pages = section
+            .page_series()
+            .iter()
+            .flat_map(|page_series| page_series.pages())
+            .map(|page| {
                 let title = page.title_text().map(|s| s.to_string()).unwrap_or_else(|| {
                     fallback_title_index += 1;
 
                     format!("Untitled Page {}", fallback_title_index)
                 });
 
-                let file_name = title.trim().replace("/", "_");
-                let file_name = self.determine_page_filename(&file_name)?;
-                let file_name = sanitize_filename::sanitize(file_name + ".html");
+                (title, page)
+            })
+            .collect::<Vec<_>>();
+
+        let titles = pages
+            .iter()
+            .map(|(title, _)| title.trim())
+            .collect::<Vec<_>>();
+        let file_names = page_file_names(&titles, self.options.page_names);
+        let keys = page_keys(&titles);
+
+        let total = pages.len();
+        let pages = pages
+            .iter()
+            .zip(file_names)
+            .zip(keys.iter().cloned())
+            .filter(|(((title, _), _), _)| self.options.filter.includes_page(title))
+            .collect::<Vec<_>>();
+
+        if total > 0 && pages.is_empty() {
+            return Ok(None);
+        }
 
-                let output_file = section_dir.join(file_name);
+        let section_dir = output_dir.join(sanitize_filename::sanitize(section.display_name()));
 
-                let mut renderer = page::Renderer::new(section_dir.clone(), self);
-                let output = renderer.render_page(page)?;
+        self.sink.create_dir(&section_dir)?;
 
-                fs::write(&output_file, output)?;
+        if self.options.dedup_assets && self.assets_dir.is_none() {
+            self.assets_dir = Some(output_dir.join("assets"));
+        }
 
-                toc.push((
-                    title,
-                    output_file
-                        .strip_prefix(output_dir)?
-                        .to_string_lossy()
-                        .to_string(),
-                    page.level(),
-                ))
-            }
+        if self.options.fonts.dir.is_some() && self.fonts_dir.is_none() {
+            self.fonts_dir = Some(output_dir.join("fonts"));
         }
 
-        let toc_html = templates::section::render(section.display_name(), toc)?;
-        let toc_name = sanitize_output_filename(section.display_name())? + ".html";
-        let toc_file = output_dir.join(toc_name);
-        fs::write(toc_file, toc_html)?;
+        self.color = section.color().map(prepare_color);
 
-        Ok(section_dir)
-    }
+        let mut toc = Vec::new();
+        let mut page_files = Vec::new();
 
-    pub(crate) fn determine_page_filename(&mut self, filename: &str) -> Result<String> {
-        let mut i = 0;
-        let mut current_filename = sanitize_filename::sanitize(filename);
+        for (((title, page), file_name), key) in pages {
+            let file_name = sanitize_filename::sanitize(file_name + ".html");
 
-        loop {
-            if !self.pages.contains(&current_filename) {
-                self.pages.insert(current_filename.clone());
+            let output_file = section_dir.join(&file_name);
 
-                return Ok(current_filename);
-            }
+            let mut renderer = page::Renderer::new(section_dir.clone(), self);
+            let output = renderer.render_page(page)?;
+
+            self.sink.write(&output_file, output)?;
 
-            i += 1;
+            toc.push((
+                title.clone(),
+                output_file
+                    .strip_prefix(output_dir)?
+                    .to_string_lossy()
+                    .to_string(),
+                page.level(),
+            ));
 
-            current_filename = format!("{}_{}", filename, i);
+            page_files.push(redirects::PageFile {
+                key,
+                title,
+                file_name,
+            });
         }
+
+        if self.options.redirects {
+            redirects::update(&section_dir, &page_files, &keys, &self.options, &self.sink)?;
+        }
+
+        let toc_html =
+            templates::section::render(section.display_name(), toc, self.color, &self.options)?;
+        self.sink.write(&toc_file(section, output_dir)?, toc_html)?;
+
+        Ok(Some(section_dir))
     }
 }
+
+/// Returns the path of the table of contents file of a section.
+pub(crate) fn toc_file(section: &Section, output_dir: &Path) -> Result<PathBuf> {
+    let toc_name = sanitize_output_filename(section.display_name())? + ".html";
+
+    Ok(output_dir.join(toc_name))
+}
+
+/// Returns the file names (without extension) of the pages of a section.
+///
+/// Pages whose name is already taken by an earlier page get a `_1`, `_2`, ...
+/// suffix. Suffixes skip the names of all other pages of the section, so a
+/// page named like a suffixed name (e.g. `Foo_1`) shifts the suffixes of the
+/// pages it would collide with.
+fn page_file_names(titles: &[&str], scheme: PageNames) -> Vec<String> {
+    let width = titles.len().to_string().len().max(3);
+
+    let names = titles
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            let name = match scheme {
+                PageNames::Title => title.replace("/", "_"),
+                PageNames::Slug => slug(title),
+                PageNames::Ordered => format!("{:0width$}-{}", i + 1, title.replace("/", "_")),
+            };
+
+            sanitize_filename::sanitize(name)
+        })
+        .collect::<Vec<_>>();
+
+    // Compare names case-insensitively as not all file systems are case-sensitive
+    let reserved = names
+        .iter()
+        .map(|name| name.to_lowercase())
+        .collect::<HashSet<_>>();
+    let mut used = HashSet::new();
+
+    names
+        .into_iter()
+        .map(|name| {
+            if used.insert(name.to_lowercase()) {
+                return name;
+            }
+
+            (1..)
+                .map(|i| format!("{}_{}", name, i))
+                .find(|candidate| {
+                    let candidate = candidate.to_lowercase();
+
+                    !reserved.contains(&candidate) && used.insert(candidate)
+                })
+                .expect("no unique page file name found")
+        })
+        .collect()
+}
+
+/// Returns the keys that identify the pages of a section between conversions.
+fn page_keys(titles: &[&str]) -> Vec<String> {
+    let mut counts = HashMap::new();
+
+    titles
+        .iter()
+        .map(|title| {
+            let count = counts.entry(*title).or_insert(0);
+            *count += 1;
+
+            match count {
+                1 => title.to_string(),
+                n => format!("{}#{}", title, n),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,256 @@
+use crate::utils::relative_url;
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use log::warn;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use tiny_http::{Header, Request, Response, StatusCode};
+
+/// Characters that are percent-encoded in URL path segments.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The URL pages poll to find out whether the output has changed.
+const VERSION_URL: &str = "/__one2html/version";
+
+/// Reloads the page when the output version changes.
+const LIVE_RELOAD_SCRIPT: &str = r#"<script>
+    (() => {
+        let version = null;
+
+        setInterval(async () => {
+            try {
+                const response = await fetch('/__one2html/version', { cache: 'no-store' });
+                const current = await response.text();
+
+                if (version !== null && current !== version) {
+                    location.reload();
+                }
+
+                version = current;
+            } catch (e) {
+                // The server is restarting or has been stopped
+            }
+        }, 1000);
+    })();
+</script>"#;
+
+/// Serves the converted notebooks over HTTP.
+pub(crate) struct Server {
+    server: tiny_http::Server,
+    root: PathBuf,
+    start_page: String,
+    live_reload: bool,
+    version: Arc<AtomicU64>,
+}
+
+impl Server {
+    /// Listen on the given address and serve the files in `root`.
+    ///
+    /// Requests to `/` are redirected to the `start_page` unless the root
+    /// contains an `index.html`. If `live_reload` is enabled, served pages are
+    /// reloaded whenever the version is incremented.
+    pub(crate) fn bind(
+        address: &str,
+        port: u16,
+        root: &Path,
+        start_page: &Path,
+        live_reload: bool,
+    ) -> Result<Self> {
+        let server = tiny_http::Server::http((address, port))
+            .map_err(|e| eyre!("Failed to listen on {}:{}: {}", address, port, e))?;
+
+        let start_page = relative_url(root, start_page)
+            .split('/')
+            .map(|part| utf8_percent_encode(part, PATH_SEGMENT).to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        Ok(Server {
+            server,
+            root: root.to_path_buf(),
+            start_page,
+            live_reload,
+            version: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}/{}", self.server.server_addr(), self.start_page)
+    }
+
+    /// Returns the version counter that triggers a reload when incremented.
+    pub(crate) fn version(&self) -> Arc<AtomicU64> {
+        self.version.clone()
+    }
+
+    pub(crate) fn run(self) {
+        for request in self.server.incoming_requests() {
+            let root = self.root.clone();
+            let start_page = self.start_page.clone();
+            let version = self.live_reload.then(|| self.version.clone());
+
+            thread::spawn(move || {
+                let url = request.url().to_string();
+
+                if let Err(e) = handle(request, &root, &start_page, version.as_deref()) {
+                    warn!("Failed to respond to request for {}: {}", url, e);
+                }
+            });
+        }
+    }
+}
+
+fn handle(
+    request: Request,
+    root: &Path,
+    start_page: &str,
+    version: Option<&AtomicU64>,
+) -> io::Result<()> {
+    let url = request.url().split(['?', '#']).next().unwrap_or_default();
+
+    if let Some(version) = version
+        && url == VERSION_URL
+    {
+        let response = Response::from_string(version.load(Ordering::SeqCst).to_string())
+            .with_header(header("Cache-Control", "no-store"));
+
+        return request.respond(response);
+    }
+
+    let path = percent_decode_str(url.trim_start_matches('/')).decode_utf8_lossy();
+    let path = Path::new(path.as_ref());
+
+    if path
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return request.respond(Response::empty(StatusCode(404)));
+    }
+
+    let mut file_path = root.join(path);
+
+    if file_path.is_dir() {
+        file_path = file_path.join("index.html");
+
+        if path.as_os_str().is_empty() && !file_path.is_file() {
+            let response = Response::empty(StatusCode(302))
+                .with_header(header("Location", &format!("/{}", start_page)));
+
+            return request.respond(response);
+        }
+    }
+
+    let Ok(mut file) = File::open(&file_path) else {
+        return request.respond(Response::empty(StatusCode(404)));
+    };
+
+    let mime = mime_guess::from_path(&file_path).first_or_octet_stream();
+    let content_type = match mime.type_() {
+        mime_guess::mime::TEXT => format!("{}; charset=utf-8", mime.essence_str()),
+        _ => mime.essence_str().to_string(),
+    };
+
+    if version.is_some() && mime.subtype() == mime_guess::mime::HTML {
+        let html = fs::read_to_string(&file_path)?;
+        let html = match html.rfind("</body>") {
+            Some(pos) => format!("{}{}{}", &html[..pos], LIVE_RELOAD_SCRIPT, &html[pos..]),
+            None => html + LIVE_RELOAD_SCRIPT,
+        };
+
+        let response = Response::from_data(html)
+            .with_header(header("Content-Type", &content_type))
+            .with_header(header("Cache-Control", "no-store"));
+
+        return request.respond(response);
+    }
+
+    let len = file.metadata()?.len();
+
+    let range = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Range"))
+        // Multiple ranges aren't supported, so the whole file is sent instead
+        .filter(|h| !h.value.as_str().contains(','))
+        .map(|h| parse_range(h.value.as_str(), len));
+
+    match range {
+        Some(Some((start, end))) => {
+            file.seek(SeekFrom::Start(start))?;
+
+            let response = Response::new(
+                StatusCode(206),
+                vec![
+                    header("Content-Type", &content_type),
+                    header("Accept-Ranges", "bytes"),
+                    header("Content-Range", &format!("bytes {}-{}/{}", start, end, len)),
+                ],
+                file.take(end - start + 1),
+                Some((end - start + 1) as usize),
+                None,
+            );
+
+            request.respond(response)
+        }
+        Some(None) => {
+            let response = Response::new(
+                StatusCode(416),
+                vec![header("Content-Range", &format!("bytes */{}", len))],
+                Cursor::new(Vec::new()),
+                Some(0),
+                None,
+            );
+
+            request.respond(response)
+        }
+        None => {
+            let response = Response::new(
+                StatusCode(200),
+                vec![
+                    header("Content-Type", &content_type),
+                    header("Accept-Ranges", "bytes"),
+                ],
+                file,
+                Some(len as usize),
+                None,
+            );
+
+            request.respond(response)
+        }
+    }
+}
+
+/// Parse the byte range of a `Range` header into inclusive bounds.
+///
+/// Returns `None` if the range can't be satisfied.
+fn parse_range(value: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+
+    if len == 0 {
+        return None;
+    }
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix = suffix.parse::<u64>().ok()?.min(len);
+            (len - suffix, len - 1)
+        }
+        (start, "") => (start.parse().ok()?, len - 1),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len - 1)),
+    };
+
+    (start <= end && start < len).then_some((start, end))
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field, value).expect("invalid header")
+}
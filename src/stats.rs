@@ -0,0 +1,328 @@
+use crate::cli::StatsOpt;
+use crate::convert::{self, Input};
+use crate::notebook::Entry;
+use crate::utils::{format_size, with_progress};
+use color_eyre::eyre::Result;
+use console::style;
+use onenote_parser::contents::{Content, EmbeddedFile, EmbeddedObject, NoteTag, OutlineElement};
+use onenote_parser::contents::{Image, OutlineItem, RichText};
+use onenote_parser::page::PageContent;
+use onenote_parser::section::Section;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Content counts of a section.
+#[derive(Debug, Default, Serialize)]
+struct Stats {
+    pages: usize,
+    outlines: usize,
+    tables: usize,
+    images: usize,
+    image_bytes: usize,
+
+    /// The number of embedded files by MIME type.
+    embedded_files: BTreeMap<String, usize>,
+
+    ink: usize,
+    equations: usize,
+
+    /// The number of note tags by label.
+    note_tags: BTreeMap<String, usize>,
+
+    /// Content the parser doesn't know how to read.
+    unknown: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SectionStats {
+    /// The `Notebook/Group/Section` path of the section.
+    section: String,
+
+    /// The section can't be parsed, e.g. because it is password protected.
+    locked: bool,
+
+    #[serde(flatten)]
+    stats: Stats,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    sections: Vec<SectionStats>,
+    total: Stats,
+}
+
+impl Stats {
+    fn of_section(section: &Section) -> Self {
+        let mut stats = Stats::default();
+
+        for page in section.page_series().iter().flat_map(|s| s.pages()) {
+            stats.pages += 1;
+
+            for content in page.contents() {
+                match content {
+                    PageContent::Outline(outline) => {
+                        stats.outlines += 1;
+                        stats.add_items(outline.items());
+                    }
+                    PageContent::Image(image) => stats.add_image(image),
+                    PageContent::EmbeddedFile(file) => stats.add_embedded_file(file),
+                    PageContent::Ink(_) => stats.ink += 1,
+                    PageContent::Unknown => stats.unknown += 1,
+                }
+            }
+        }
+
+        stats
+    }
+
+    fn add_items(&mut self, items: &[OutlineItem]) {
+        for item in items {
+            match item {
+                OutlineItem::Group(group) => self.add_items(group.outlines()),
+                OutlineItem::Element(element) => self.add_element(element),
+            }
+        }
+    }
+
+    fn add_element(&mut self, element: &OutlineElement) {
+        for content in element.contents() {
+            match content {
+                Content::RichText(text) => self.add_rich_text(text),
+                Content::Table(table) => {
+                    self.tables += 1;
+                    self.add_note_tags(table.note_tags());
+
+                    for cell in table.contents().iter().flat_map(|row| row.contents()) {
+                        for element in cell.contents() {
+                            self.add_element(element);
+                        }
+                    }
+                }
+                Content::Image(image) => self.add_image(image),
+                Content::EmbeddedFile(file) => self.add_embedded_file(file),
+                Content::Ink(_) => self.ink += 1,
+                Content::Unknown => self.unknown += 1,
+            }
+        }
+
+        self.add_items(element.children());
+    }
+
+    fn add_rich_text(&mut self, text: &RichText) {
+        self.add_note_tags(text.note_tags());
+
+        if !text.math_inline_objects().is_empty() {
+            self.equations += 1;
+        }
+
+        // Handwriting embedded in a paragraph counts as one ink drawing
+        if text
+            .embedded_objects()
+            .iter()
+            .any(|object| matches!(object, EmbeddedObject::Ink(_)))
+        {
+            self.ink += 1;
+        }
+    }
+
+    fn add_image(&mut self, image: &Image) {
+        self.images += 1;
+        self.image_bytes += image.data().map(|data| data.len()).unwrap_or_default();
+        self.add_note_tags(image.note_tags());
+    }
+
+    fn add_embedded_file(&mut self, file: &EmbeddedFile) {
+        let mime = mime_guess::from_path(file.filename())
+            .first_or_octet_stream()
+            .essence_str()
+            .to_string();
+
+        *self.embedded_files.entry(mime).or_default() += 1;
+        self.add_note_tags(file.note_tags());
+    }
+
+    fn add_note_tags(&mut self, tags: &[NoteTag]) {
+        for tag in tags {
+            let label = tag
+                .definition()
+                .map(|definition| definition.label())
+                .filter(|label| !label.is_empty())
+                .unwrap_or("Unnamed")
+                .to_string();
+
+            *self.note_tags.entry(label).or_default() += 1;
+        }
+    }
+
+    fn add(&mut self, other: &Stats) {
+        self.pages += other.pages;
+        self.outlines += other.outlines;
+        self.tables += other.tables;
+        self.images += other.images;
+        self.image_bytes += other.image_bytes;
+        self.ink += other.ink;
+        self.equations += other.equations;
+        self.unknown += other.unknown;
+
+        for (mime, count) in &other.embedded_files {
+            *self.embedded_files.entry(mime.clone()).or_default() += count;
+        }
+
+        for (label, count) in &other.note_tags {
+            *self.note_tags.entry(label.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Print the content counts of each section of the input files.
+pub(crate) fn stats(opt: &StatsOpt) -> Result<()> {
+    let mut sections = Vec::new();
+
+    for job in convert::jobs(&opt.input.input, Path::new(""))? {
+        let input = with_progress("Parsing input file...", || convert::parse(&job.path))?;
+
+        match input {
+            Input::Section(section) => sections.push(SectionStats {
+                section: section.display_name().to_string(),
+                locked: false,
+                stats: Stats::of_section(&section),
+            }),
+            Input::Notebook(name, notebook) => {
+                add_entries(&mut sections, &name, &notebook.entries());
+            }
+        }
+    }
+
+    let mut total = Stats::default();
+    for section in &sections {
+        total.add(&section.stats);
+    }
+
+    let report = Report { sections, total };
+
+    if opt.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+
+    Ok(())
+}
+
+/// Add the stats of the sections in the notebook entries below `path`.
+fn add_entries(sections: &mut Vec<SectionStats>, path: &str, entries: &[Entry]) {
+    for entry in entries {
+        match entry {
+            Entry::Section(section) => sections.push(SectionStats {
+                section: format!("{}/{}", path, section.display_name()),
+                locked: false,
+                stats: Stats::of_section(section),
+            }),
+            Entry::SectionGroup(name, entries) => {
+                add_entries(sections, &format!("{}/{}", path, name), entries)
+            }
+            Entry::Locked(name) => sections.push(SectionStats {
+                section: format!("{}/{}", path, name),
+                locked: true,
+                stats: Stats::default(),
+            }),
+        }
+    }
+}
+
+fn print_report(report: &Report) {
+    let names = report
+        .sections
+        .iter()
+        .map(|section| {
+            if section.locked {
+                format!("{} (locked)", section.section)
+            } else {
+                section.section.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let rows = names
+        .iter()
+        .map(String::as_str)
+        .zip(report.sections.iter().map(|section| &section.stats))
+        .chain([("Total", &report.total)])
+        .collect::<Vec<_>>();
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max("Section".len());
+
+    println!(
+        "{}",
+        style(format!(
+            "{:width$}  {:>5}  {:>8}  {:>6}  {:>6}  {:>11}  {:>5}  {:>3}  {:>9}  {:>9}  {:>7}",
+            "Section",
+            "Pages",
+            "Outlines",
+            "Tables",
+            "Images",
+            "Image size",
+            "Files",
+            "Ink",
+            "Equations",
+            "Note tags",
+            "Unknown"
+        ))
+        .bright()
+    );
+
+    for (name, stats) in &rows {
+        println!(
+            "{:width$}  {:>5}  {:>8}  {:>6}  {:>6}  {:>11}  {:>5}  {:>3}  {:>9}  {:>9}  {:>7}",
+            name,
+            stats.pages,
+            stats.outlines,
+            stats.tables,
+            stats.images,
+            format_size(stats.image_bytes),
+            stats.embedded_files.values().sum::<usize>(),
+            stats.ink,
+            stats.equations,
+            stats.note_tags.values().sum::<usize>(),
+            stats.unknown
+        );
+    }
+
+    print_breakdown("Embedded files by MIME type", &rows, |stats| {
+        &stats.embedded_files
+    });
+    print_breakdown("Note tags by type", &rows, |stats| &stats.note_tags);
+}
+
+/// Print the per-section counts of a breakdown, skipping sections without
+/// any entries.
+fn print_breakdown(
+    title: &str,
+    rows: &[(&str, &Stats)],
+    counts: impl Fn(&Stats) -> &BTreeMap<String, usize>,
+) {
+    if rows.iter().all(|(_, stats)| counts(stats).is_empty()) {
+        return;
+    }
+
+    println!();
+    println!("{}", style(title).bright());
+
+    for (name, stats) in rows {
+        if counts(stats).is_empty() {
+            continue;
+        }
+
+        println!("  {}", name);
+
+        for (key, count) in counts(stats) {
+            println!("    {:>5}  {}", count, key);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::options::Options;
+use askama::Template;
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+
+#[derive(Template)]
+#[template(path = "index.html")]
+struct IndexTemplate<'a> {
+    name: &'a str,
+    entries: &'a [Entry],
+    options: &'a Options,
+}
+
+#[derive(Debug)]
+pub(crate) struct Entry {
+    pub(crate) name: String,
+    pub(crate) path: String,
+}
+
+pub(crate) fn render(name: &str, entries: &[Entry], options: &Options) -> Result<String> {
+    let template = IndexTemplate {
+        name,
+        entries,
+        options,
+    };
+
+    template
+        .render()
+        .wrap_err("Failed to render index template")
+}
+
+mod filters {
+    pub(crate) use crate::templates::url_encode as encode;
+}
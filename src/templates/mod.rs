@@ -1,8 +1,10 @@
 use percent_encoding::AsciiSet;
 use std::path;
 
+pub(crate) mod index;
 pub(crate) mod notebook;
 pub(crate) mod page;
+pub(crate) mod redirect;
 pub(crate) mod section;
 
 const ASCII_SET: AsciiSet = percent_encoding::NON_ALPHANUMERIC.remove(path::MAIN_SEPARATOR as u8);
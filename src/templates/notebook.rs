@@ -1,4 +1,5 @@
 use crate::notebook::RgbColor;
+use crate::options::Options;
 use askama::Template;
 use color_eyre::Result;
 use color_eyre::eyre::WrapErr;
@@ -8,11 +9,15 @@ use color_eyre::eyre::WrapErr;
 struct NotebookTemplate<'a> {
     name: &'a str,
     toc: &'a [Toc],
+    options: &'a Options,
 }
 
 pub(crate) enum Toc {
     Section(Section),
     SectionGroup(String, Vec<Section>),
+
+    /// The table of contents of the sections in the recycle bin.
+    DeletedItems(String),
 }
 
 #[derive(Debug)]
@@ -20,10 +25,13 @@ pub(crate) struct Section {
     pub(crate) name: String,
     pub(crate) path: String,
     pub(crate) color: Option<RgbColor>,
+
+    /// The section couldn't be read, e.g. because it is password protected.
+    pub(crate) locked: bool,
 }
 
-pub(crate) fn render(name: &str, toc: &[Toc]) -> Result<String> {
-    let template = NotebookTemplate { name, toc };
+pub(crate) fn render(name: &str, toc: &[Toc], options: &Options) -> Result<String> {
+    let template = NotebookTemplate { name, toc, options };
 
     template
         .render()
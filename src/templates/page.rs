@@ -1,3 +1,5 @@
+use crate::notebook::RgbColor;
+use crate::options::Options;
 use crate::utils::StyleSet;
 use askama::Template;
 use color_eyre::Result;
@@ -11,12 +13,20 @@ struct PageTemplate<'a> {
     name: &'a str,
     content: &'a str,
     global_styles: Vec<(&'a String, &'a StyleSet)>,
+    default_font: &'a str,
+    font_faces: &'a str,
+    color: Option<RgbColor>,
+    options: &'a Options,
 }
 
 pub(crate) fn render(
     name: &str,
     content: &str,
     global_styles: &HashMap<String, StyleSet>,
+    default_font: &str,
+    font_faces: &str,
+    color: Option<RgbColor>,
+    options: &Options,
 ) -> Result<String> {
     PageTemplate {
         name,
@@ -25,6 +35,10 @@ pub(crate) fn render(
             .iter()
             .sorted_by(|(a, _), (b, _)| Ord::cmp(a, b))
             .collect(),
+        default_font,
+        font_faces,
+        color,
+        options,
     }
     .render()
     .wrap_err("Failed to render page template")
@@ -0,0 +1,28 @@
+use crate::options::Options;
+use askama::Template;
+use color_eyre::Result;
+use color_eyre::eyre::WrapErr;
+
+#[derive(Template)]
+#[template(path = "redirect.html")]
+struct RedirectTemplate<'a> {
+    title: &'a str,
+    target: &'a str,
+    options: &'a Options,
+}
+
+pub(crate) fn render(title: &str, target: &str, options: &Options) -> Result<String> {
+    let template = RedirectTemplate {
+        title,
+        target,
+        options,
+    };
+
+    template
+        .render()
+        .wrap_err("Failed to render redirect template")
+}
+
+mod filters {
+    pub(crate) use crate::templates::url_encode as encode;
+}
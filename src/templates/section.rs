@@ -1,3 +1,5 @@
+use crate::notebook::RgbColor;
+use crate::options::Options;
 use askama::Template;
 use color_eyre::Result;
 use color_eyre::eyre::WrapErr;
@@ -7,6 +9,8 @@ use color_eyre::eyre::WrapErr;
 struct NotebookTemplate<'a> {
     name: &'a str,
     pages: Vec<Page<'a>>,
+    color: Option<RgbColor>,
+    options: &'a Options,
 }
 
 struct Page<'a> {
@@ -15,7 +19,12 @@ struct Page<'a> {
     level: i32,
 }
 
-pub(crate) fn render(name: &str, pages: Vec<(String, String, i32)>) -> Result<String> {
+pub(crate) fn render(
+    name: &str,
+    pages: Vec<(String, String, i32)>,
+    color: Option<RgbColor>,
+    options: &Options,
+) -> Result<String> {
     let template = NotebookTemplate {
         name,
         pages: pages
@@ -26,6 +35,8 @@ pub(crate) fn render(name: &str, pages: Vec<(String, String, i32)>) -> Result<St
                 level: *level,
             })
             .collect(),
+        color,
+        options,
     };
 
     template
@@ -3,6 +3,7 @@ use itertools::Itertools;
 use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Display;
+use std::iter::repeat_n;
 use std::path::Path;
 use std::time::Duration;
 
@@ -38,6 +39,106 @@ pub(crate) fn sanitize_output_filename(filename: &str) -> Result<String> {
     Ok(sanitized)
 }
 
+/// Convert a title to lowercase ASCII words separated by dashes, e.g.
+/// `Über uns` to `uber-uns`.
+pub(crate) fn slug(title: &str) -> String {
+    let mut slug = String::new();
+
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if let Some(folded) = fold_to_ascii(c) {
+            slug.push_str(folded);
+        } else if ('\u{300}'..='\u{36f}').contains(&c) {
+            // Skip combining diacritical marks
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+
+    if slug.is_empty() {
+        "page".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Map lowercase Latin letters with diacritics to ASCII.
+fn fold_to_ascii(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+
+    Some(folded)
+}
+
+/// Format a file size for display, e.g. `12.3 KB`.
+pub(crate) fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Build a relative URL that points from the directory `from` to the file `to`.
+pub(crate) fn relative_url(from: &Path, to: &Path) -> String {
+    let from = from.components().collect_vec();
+    let to = to.components().collect_vec();
+
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    repeat_n("..".to_string(), from.len() - common)
+        .chain(
+            to[common..]
+                .iter()
+                .map(|c| c.as_os_str().to_string_lossy().to_string()),
+        )
+        .join("/")
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 pub(crate) struct AttributeSet(HashMap<&'static str, String>);
 
 impl AttributeSet {
@@ -0,0 +1,253 @@
+use crate::convert::{self, Job};
+use crate::discovery::RECYCLE_BIN;
+use crate::options::Options;
+use crate::output::Sink;
+use color_eyre::Result;
+use console::style;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The modification time and size of a file, `None` if the file was removed.
+type FileState = Option<(SystemTime, u64)>;
+
+/// What needs to be converted again when a file changes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Target {
+    /// Convert a section or a whole notebook.
+    Job(usize),
+
+    /// Convert a single section of a notebook.
+    NotebookSection(usize, PathBuf),
+
+    /// Convert all inputs, e.g. because notebooks were added to an input folder.
+    All,
+}
+
+/// Watch the input files and convert the notebooks and sections again when
+/// they change. `on_rebuild` is called after each conversion.
+///
+/// Files are polled instead of relying on file system events, which aren't
+/// reliable on network shares. A changed file is converted once its size and
+/// modification time haven't changed for one polling interval, so files that
+/// are still being synced aren't read.
+pub(crate) fn watch(
+    inputs: &[PathBuf],
+    output_dir: &Path,
+    options: &Options,
+    mut on_rebuild: impl FnMut(),
+) -> Result<()> {
+    let mut jobs = convert::jobs(inputs, output_dir)?;
+    let mut files = snapshot(&jobs);
+    let mut pending: BTreeMap<PathBuf, FileState> = BTreeMap::new();
+
+    println!("Watching for changes...");
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let current_jobs = match convert::jobs(inputs, output_dir) {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                eprintln!("{} to find input files: {}", style("Failed").red(), e);
+                continue;
+            }
+        };
+
+        let current = snapshot(&current_jobs);
+
+        // Files that didn't change since the last poll have stabilized
+        let stable = pending
+            .iter()
+            .filter(|(path, state)| current.get(*path).copied().flatten() == **state)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+
+        for (path, state) in &current {
+            if files.get(path) != Some(state) && !stable.contains(path) {
+                pending.insert(path.clone(), *state);
+            }
+        }
+
+        for path in files.keys() {
+            if !current.contains_key(path) && !stable.contains(path) {
+                pending.insert(path.clone(), None);
+            }
+        }
+
+        if stable.is_empty() {
+            continue;
+        }
+
+        let jobs_changed = current_jobs != jobs;
+        jobs = current_jobs;
+
+        let targets = if jobs_changed {
+            BTreeSet::from([Target::All])
+        } else {
+            stable
+                .iter()
+                .map(|path| target(&jobs, path, files.contains_key(path)))
+                .collect()
+        };
+
+        for path in &stable {
+            pending.remove(path);
+        }
+
+        files = current;
+
+        for target in targets {
+            rebuild(&target, &jobs, inputs, output_dir, options);
+        }
+
+        on_rebuild();
+    }
+}
+
+/// Returns the state of all files used by the conversion jobs.
+fn snapshot(jobs: &[Job]) -> BTreeMap<PathBuf, FileState> {
+    let mut files = BTreeMap::new();
+
+    for job in jobs {
+        let paths = match job.path.parent() {
+            Some(dir) if is_notebook(job) => notebook_files(dir),
+            _ => vec![job.path.clone()],
+        };
+
+        for path in paths {
+            let state = fs::metadata(&path)
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                .ok();
+
+            files.insert(path, state);
+        }
+    }
+
+    files
+}
+
+/// Returns the table of contents and all section files of a notebook.
+fn notebook_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            files.extend(notebook_files(&path));
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "one" || ext == "onetoc2")
+        {
+            files.push(path);
+        }
+    }
+
+    files
+}
+
+fn is_notebook(job: &Job) -> bool {
+    job.path.extension().is_some_and(|ext| ext == "onetoc2")
+}
+
+/// Determine what to convert again for a changed file.
+fn target(jobs: &[Job], path: &Path, existed: bool) -> Target {
+    for (i, job) in jobs.iter().enumerate() {
+        if job.path == path {
+            return Target::Job(i);
+        }
+
+        let Some(notebook_dir) = job.path.parent().filter(|_| is_notebook(job)) else {
+            continue;
+        };
+
+        if !path.starts_with(notebook_dir) {
+            continue;
+        }
+
+        let in_recycle_bin = path
+            .strip_prefix(notebook_dir)
+            .is_ok_and(|path| path.starts_with(RECYCLE_BIN));
+
+        // Added and removed sections change the notebook's table of contents
+        if existed && path.is_file() && !in_recycle_bin {
+            return Target::NotebookSection(i, path.to_path_buf());
+        }
+
+        return Target::Job(i);
+    }
+
+    Target::All
+}
+
+fn rebuild(
+    target: &Target,
+    jobs: &[Job],
+    inputs: &[PathBuf],
+    output_dir: &Path,
+    options: &Options,
+) {
+    let start = Instant::now();
+
+    let (name, result) = match target {
+        Target::Job(i) => {
+            let job = &jobs[*i];
+            let result = fs::create_dir_all(&job.output_dir)
+                .map_err(Into::into)
+                .and_then(|_| {
+                    convert::convert(&job.path, &job.output_dir, options, &Sink::Directory)
+                        .map(|_| ())
+                });
+
+            (display_name(&job.path), result)
+        }
+        Target::NotebookSection(i, path) => {
+            let job = &jobs[*i];
+            let result = convert::convert_notebook_section(
+                &job.path,
+                path,
+                &job.output_dir,
+                options,
+                &Sink::Directory,
+            );
+
+            (display_name(path), result)
+        }
+        Target::All => (
+            "all inputs".to_string(),
+            convert::convert_all(inputs, output_dir, options, &Sink::Directory).map(|_| ()),
+        ),
+    };
+
+    match result {
+        Ok(()) => println!(
+            "{} {} in {:.1}s",
+            style("Rebuilt").green(),
+            name,
+            start.elapsed().as_secs_f32()
+        ),
+        Err(e) => eprintln!("{} to rebuild {}: {:?}", style("Failed").red(), name, e),
+    }
+}
+
+/// Returns the name of a section file or the notebook a `.onetoc2` file belongs to.
+fn display_name(path: &Path) -> String {
+    let path = match path.extension() {
+        Some(ext) if ext == "onetoc2" => path.parent().unwrap_or(path),
+        _ => path,
+    };
+
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
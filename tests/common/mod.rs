@@ -0,0 +1,71 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A temporary directory that is removed when dropped.
+pub struct TempDir {
+    pub path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "one2html-test-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        TempDir { path }
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Copy the sample notebook into `dir` and return the notebook folder.
+pub fn sample_notebook(dir: &Path) -> PathBuf {
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/samples/Test Notebook");
+    let notebook = dir.join("Test Notebook");
+
+    fs::create_dir_all(&notebook).unwrap();
+
+    for entry in fs::read_dir(source).unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, notebook.join(path.file_name().unwrap())).unwrap();
+    }
+
+    notebook
+}
+
+/// Replace a section of a notebook with a file that can't be parsed.
+pub fn lock_section(notebook: &Path, name: &str) {
+    fs::write(notebook.join(format!("{}.one", name)), b"locked").unwrap();
+}
+
+pub fn one2html() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_one2html"))
+}
+
+/// Run one2html and panic if it fails.
+pub fn run(args: &[&str]) -> Output {
+    let output = one2html().args(args).output().unwrap();
+
+    assert!(
+        output.status.success(),
+        "one2html failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{TempDir, lock_section, one2html, run, sample_notebook};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+#[test]
+fn convert_notebook_with_locked_section() {
+    let dir = TempDir::new();
+    let notebook = sample_notebook(&dir.path);
+    lock_section(&notebook, "New Section 1");
+
+    let output_dir = dir.path.join("output");
+    let toc = notebook.join("Open Notebook.onetoc2");
+    run(&[
+        "-i",
+        toc.to_str().unwrap(),
+        "-o",
+        output_dir.to_str().unwrap(),
+    ]);
+
+    let toc_html = fs::read_to_string(output_dir.join("Test Notebook.html")).unwrap();
+    assert!(toc_html.contains("New Section 1"));
+    assert!(toc_html.contains("New Section 2"));
+    assert!(
+        output_dir
+            .join("Test Notebook/New Section 2/Test Page 3.html")
+            .is_file()
+    );
+}
+
+#[test]
+fn serve_notebook_with_locked_section() {
+    let dir = TempDir::new();
+    let notebook = sample_notebook(&dir.path);
+    lock_section(&notebook, "New Section 1");
+
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    // Convert a standalone section before the notebook, so its output is
+    // already there when the notebook is parsed
+    let section = notebook.join("New Section 2.one");
+    let toc = notebook.join("Open Notebook.onetoc2");
+
+    let mut child = one2html()
+        .args(["serve", "--port", &port.to_string(), "-i"])
+        .args([&section, &toc])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let serving = stdout
+        .lines()
+        .map(Result::unwrap)
+        .find_map(|line| Some(line.split_once("Serving ")?.1.to_string()));
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let serving = serving.expect("server didn't start");
+    let output_dir = PathBuf::from(serving.rsplit_once(" at ").unwrap().0);
+    let output = TempDir { path: output_dir };
+
+    assert!(output.path.join("New Section 2.html").is_file());
+    assert!(output.path.join("Test Notebook.html").is_file());
+    assert!(!output.path.join("Open Notebook.onetoc2").exists());
+}
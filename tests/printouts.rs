@@ -0,0 +1,27 @@
+mod common;
+
+use common::{TempDir, run};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn render_page_printout() {
+    let dir = TempDir::new();
+    let section =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/samples/Test Notebook/New Section 2.one");
+
+    run(&[
+        "-i",
+        section.to_str().unwrap(),
+        "-o",
+        dir.path.to_str().unwrap(),
+    ]);
+
+    let page = fs::read_to_string(dir.path.join("New Section 2/Test Page 3.html")).unwrap();
+
+    assert!(page.contains("<div class=\"printout\""));
+    assert!(page.contains("Page <span class=\"printout-current\">1</span> of 1"));
+
+    // The page has no embedded file the printout could link to
+    assert!(!page.contains("printout-download"));
+}